@@ -0,0 +1,256 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+/// Maximal amount of bets and raises per street in fixed-limit games, including the big blind.
+pub const FIXED_LIMIT_RAISE_CAP: usize = 4;
+
+/// The rules which limit how much a player may bet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(clippy::enum_variant_names)]
+pub enum BettingStructure {
+    /// A player may raise up to his whole stack.
+    NoLimit,
+
+    /// A raise may be at most the size of the pot after calling.
+    PotLimit,
+
+    /// Every bet and raise is exactly one small bet on the first two streets and one big bet on the last two.
+    /// At most `FIXED_LIMIT_RAISE_CAP` bets and raises are allowed per street.
+    FixedLimit,
+}
+
+/// Which straddles players may post before the cards are dealt.
+/// A straddle is a voluntary third blind of twice the big blind, or twice the previous straddle.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StraddleRule {
+    /// No straddles are allowed.
+    Off,
+
+    /// Either the player after the big blind or the player on the button may straddle once.
+    Single,
+
+    /// Like `Single`, but the players after a straddle may re-straddle one after another.
+    Restraddle,
+}
+
+/// Everything a betting structure needs to know about the player who has to act.
+/// All bets are the total amounts of the whole hand.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BettingSituation {
+    /// The bet of the player so far.
+    pub current_bet: usize,
+
+    /// The highest bet of all players.
+    pub highest_bet: usize,
+
+    /// The sum of the bets of all players.
+    pub pot: usize,
+
+    /// The coins of the player, including his current bet.
+    pub stack: usize,
+
+    /// The big blind of the hand, which is also the small bet of fixed-limit games.
+    pub big_blind: usize,
+
+    /// The street of the hand, starting with 0 before the flop.
+    pub street: usize,
+
+    /// The amount of bets and raises on this street so far, including the big blind.
+    pub raises: usize,
+}
+
+/// The amounts a player may raise to. Raising to `minimal` means calling (or going all in, if the stack is short).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BetRange {
+    pub minimal: usize,
+    pub maximal: usize,
+}
+
+impl BettingStructure {
+    /// Returns the range of amounts the player may raise to.
+    pub fn legal_range(&self, situation: &BettingSituation) -> BetRange {
+        let highest_bet = situation.highest_bet;
+        let limit = match self {
+            Self::NoLimit => usize::MAX,
+            Self::PotLimit => {
+                let call = highest_bet - situation.current_bet;
+                highest_bet + situation.pot + call
+            }
+            Self::FixedLimit if situation.raises >= FIXED_LIMIT_RAISE_CAP => highest_bet,
+            Self::FixedLimit => highest_bet + Self::fixed_bet_size(situation),
+        };
+        BetRange {
+            minimal: std::cmp::min(highest_bet, situation.stack),
+            maximal: std::cmp::min(std::cmp::max(limit, highest_bet), situation.stack),
+        }
+    }
+
+    /// Turns the amount a player wants to raise to into a legal amount.
+    /// Amounts out of the legal range are clamped, in fixed-limit games every raise is exactly one bet.
+    pub fn validate(&self, situation: &BettingSituation, amount: usize) -> usize {
+        let range = self.legal_range(situation);
+        let amount = amount.clamp(range.minimal, range.maximal);
+        match self {
+            Self::FixedLimit if amount > range.minimal => range.maximal,
+            _ => amount,
+        }
+    }
+
+    /// Returns the size of a bet in fixed-limit games: the small bet before the turn, the big bet afterwards.
+    fn fixed_bet_size(situation: &BettingSituation) -> usize {
+        if situation.street < 2 {
+            situation.big_blind
+        } else {
+            2 * situation.big_blind
+        }
+    }
+}
+
+impl TryFrom<&str> for BettingStructure {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "nl" => Ok(Self::NoLimit),
+            "pl" => Ok(Self::PotLimit),
+            "fl" => Ok(Self::FixedLimit),
+            v => Err(format!("No betting structure: {}", v)),
+        }
+    }
+}
+
+impl TryFrom<&str> for StraddleRule {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "off" => Ok(Self::Off),
+            "single" => Ok(Self::Single),
+            "restraddle" => Ok(Self::Restraddle),
+            v => Err(format!("No straddle rule: {}", v)),
+        }
+    }
+}
+
+impl fmt::Display for BettingStructure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::NoLimit => "nl",
+            Self::PotLimit => "pl",
+            Self::FixedLimit => "fl",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for StraddleRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Off => "off",
+            Self::Single => "single",
+            Self::Restraddle => "restraddle",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Utility function for creating a situation with a pot of 30, where the player has to call 10 more.
+    fn situation(street: usize, raises: usize, stack: usize) -> BettingSituation {
+        BettingSituation {
+            current_bet: 10,
+            highest_bet: 20,
+            pot: 30,
+            stack,
+            big_blind: 10,
+            street,
+            raises,
+        }
+    }
+
+    /// Testing the legal range of every betting structure.
+    #[test]
+    fn test_legal_ranges() {
+        let test_cases = vec![
+            (BettingStructure::NoLimit, situation(0, 1, 1000), (20, 1000)),
+            // Call 10, then raise by the pot of 40
+            (BettingStructure::PotLimit, situation(0, 1, 1000), (20, 60)),
+            (BettingStructure::PotLimit, situation(0, 1, 50), (20, 50)),
+            // Small bet before the turn, big bet afterwards
+            (
+                BettingStructure::FixedLimit,
+                situation(1, 1, 1000),
+                (20, 30),
+            ),
+            (
+                BettingStructure::FixedLimit,
+                situation(2, 1, 1000),
+                (20, 40),
+            ),
+            // The raise cap is reached, only calling is allowed
+            (
+                BettingStructure::FixedLimit,
+                situation(3, 4, 1000),
+                (20, 20),
+            ),
+            // A short stack may only go all in
+            (BettingStructure::NoLimit, situation(0, 1, 15), (15, 15)),
+        ];
+        for (structure, situation, (minimal, maximal)) in test_cases {
+            let range = structure.legal_range(&situation);
+            assert_eq!(
+                BetRange { minimal, maximal },
+                range,
+                "Failed: {} {:?}",
+                structure,
+                situation
+            );
+        }
+    }
+
+    /// Testing the validation of the amounts players want to raise to.
+    #[test]
+    fn test_validation() {
+        let test_cases = vec![
+            (BettingStructure::NoLimit, 0, 20),
+            (BettingStructure::NoLimit, 35, 35),
+            (BettingStructure::NoLimit, usize::MAX, 1000),
+            (BettingStructure::PotLimit, 45, 45),
+            (BettingStructure::PotLimit, 500, 60),
+            // Every raise is exactly one bet
+            (BettingStructure::FixedLimit, 21, 30),
+            (BettingStructure::FixedLimit, 500, 30),
+            (BettingStructure::FixedLimit, 20, 20),
+        ];
+        for (structure, amount, expected) in test_cases {
+            let validated = structure.validate(&situation(0, 1, 1000), amount);
+            assert_eq!(
+                expected, validated,
+                "Failed: {} {}. Expected {}, got {}",
+                structure, amount, expected, validated
+            );
+        }
+    }
+
+    /// Testing the conversion from and to names.
+    #[test]
+    fn test_names() {
+        for structure in [
+            BettingStructure::NoLimit,
+            BettingStructure::PotLimit,
+            BettingStructure::FixedLimit,
+        ] {
+            let name = structure.to_string();
+            assert_eq!(Ok(structure), BettingStructure::try_from(name.as_str()));
+        }
+        assert!(BettingStructure::try_from("limit").is_err());
+        assert_eq!(
+            Ok(StraddleRule::Restraddle),
+            StraddleRule::try_from("restraddle")
+        );
+        assert!(StraddleRule::try_from("mississippi").is_err());
+    }
+}
@@ -0,0 +1,831 @@
+use itertools::Itertools;
+use std::cmp::max;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// A playing card.
+///
+/// A card is written as its color `A` to `D`, followed by its value as a digit in base 13, starting with 0 for a two:
+///
+/// ```
+/// use nolimittexasholdem::Card;
+///
+/// let ace = Card::try_from("DC").unwrap();
+/// assert_eq!(3, ace.color());
+/// assert_eq!(12, ace.value());
+/// assert!(Card::try_from("E0").is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Card {
+    pub value: u8,
+}
+
+impl Card {
+    /// Returns the Card's color.
+    pub fn color(&self) -> u8 {
+        self.value / 13
+    }
+
+    /// Returns the Card's value.
+    pub fn value(&self) -> u8 {
+        self.value % 13
+    }
+}
+
+impl From<(u8, u8)> for Card {
+    fn from(value: (u8, u8)) -> Self {
+        Self {
+            value: value.0 * 13 + value.1,
+        }
+    }
+}
+
+impl TryFrom<&str> for Card {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let c: Vec<_> = value.chars().collect();
+        if c.len() != 2 {
+            Err(c.len().to_string())
+        } else {
+            let col = match c[0] {
+                'A' => 0,
+                'B' => 1,
+                'C' => 2,
+                'D' => 3,
+                p => return Err(format!("No color: {}", p).to_string()),
+            };
+
+            let val = u8::from_str_radix(c[1].to_string().as_str(), 13);
+            match val {
+                Ok(val) => Ok(Card::from((col, val))),
+                Err(_) => Err("Conversion failed".into()),
+            }
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const SUITS: [&str; 4] = ["♣︎", "♦︎", "♥︎", "♠︎"];
+        const VALUES: [&str; 13] = [
+            "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K", "A",
+        ];
+        write!(
+            f,
+            "{}{}",
+            VALUES[self.value() as usize],
+            SUITS[self.color() as usize]
+        )
+    }
+}
+
+/// The rules by which hands of five cards are ranked.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HandRanking {
+    /// The usual ranking of a full deck, where A-2-3-4-5 is the lowest straight.
+    Standard,
+
+    /// The ranking of a short deck without 2 to 5: a flush beats a full house and A-6-7-8-9 is the lowest straight.
+    /// If `trips_beat_straight` is set, three of a kind also beats a straight.
+    ShortDeck { trips_beat_straight: bool },
+
+    /// The ranking of deuce-to-seven lowball, where the ace is always the highest card and A-2-3-4-5 is no straight.
+    DeuceToSeven,
+}
+
+/// Returns the value of the hand, which is the value of its best five cards.
+pub fn value_of_hand(cards: &[(u8, u8)]) -> usize {
+    assert_eq!(cards.len(), 7);
+
+    value_of_best_five(cards, HandRanking::Standard)
+}
+
+/// Returns the value of a short deck hand, which is the value of its best five cards.
+/// The values are only comparable with values of the same ranking.
+pub fn value_of_short_deck_hand(cards: &[(u8, u8)], trips_beat_straight: bool) -> usize {
+    assert_eq!(cards.len(), 7);
+
+    value_of_best_five(
+        cards,
+        HandRanking::ShortDeck {
+            trips_beat_straight,
+        },
+    )
+}
+
+/// Returns the value of a hand of exactly five cards.
+/// The values are comparable with the values of `value_of_hand`.
+pub fn value_of_five(cards: &[(u8, u8)]) -> usize {
+    value_of_ranked_five(cards, HandRanking::Standard)
+}
+
+/// Returns the value of a hand of exactly five cards according to the ranking.
+pub fn value_of_ranked_five(cards: &[(u8, u8)], ranking: HandRanking) -> usize {
+    assert_eq!(cards.len(), 5);
+
+    // Values of the lowest straight, where the ace counts as lowest card
+    let lowest_straight = match ranking {
+        HandRanking::Standard => Some([12, 3, 2, 1, 0]),
+        HandRanking::ShortDeck { .. } => Some([12, 7, 6, 5, 4]),
+        HandRanking::DeuceToSeven => None,
+    };
+
+    let mut values = [0u8; 5];
+    for (value, card) in values.iter_mut().zip(cards) {
+        *value = card.1;
+    }
+    values.sort_unstable_by(|a, b| b.cmp(a));
+    let is_flush = cards.iter().all(|card| card.0 == cards[0].0);
+    let is_distinct = values.windows(2).all(|w| w[0] != w[1]);
+    let straight_high = if is_distinct && values[0] - values[4] == 4 {
+        Some(values[0])
+    } else if Some(values) == lowest_straight {
+        // The ace counts as lowest card
+        Some(values[1])
+    } else {
+        None
+    };
+
+    // Values grouped by their occurrences, biggest groups first, then highest values first
+    let mut groups = [(0usize, 0u8); 5];
+    let mut group_count = 0;
+    for &value in &values {
+        if group_count > 0 && groups[group_count - 1].1 == value {
+            groups[group_count - 1].0 += 1;
+        } else {
+            groups[group_count] = (1, value);
+            group_count += 1;
+        }
+    }
+    let groups = &mut groups[..group_count];
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    let mut counts = [0usize; 5];
+    let mut v = [0u8; 5];
+    for (i, group) in groups.iter().enumerate() {
+        counts[i] = group.0;
+        v[i] = group.1;
+    }
+
+    let value = match (straight_high, is_flush, &counts[..group_count]) {
+        (Some(high), true, _) => calculate_value(STRAIGHT_FLUSH_OFFSET, 0, 0, 0, 0, high),
+        (_, _, [4, 1]) => calculate_value(FOUR_OFFSET, 0, 0, 0, v[0], v[1]),
+        (_, _, [3, 2]) => calculate_value(FULL_HOUSE_OFFSET, 0, 0, 0, v[0], v[1]),
+        (_, true, _) => calculate_value(
+            FLUSH_OFFSET,
+            values[0],
+            values[1],
+            values[2],
+            values[3],
+            values[4],
+        ),
+        (Some(high), _, _) => calculate_value(STRAIGHT_OFFSET, 0, 0, 0, 0, high),
+        (_, _, [3, 1, 1]) => calculate_value(THREE_OFFSET, 0, 0, v[0], v[1], v[2]),
+        (_, _, [2, 2, 1]) => calculate_value(TWO_PAIR_OFFSET, 0, 0, v[0], v[1], v[2]),
+        (_, _, [2, 1, 1, 1]) => calculate_value(PAIR_OFFSET, 0, v[0], v[1], v[2], v[3]),
+        _ => calculate_value(
+            HIGH_CARD_OFFSET,
+            values[0],
+            values[1],
+            values[2],
+            values[3],
+            values[4],
+        ),
+    };
+    match ranking {
+        HandRanking::Standard | HandRanking::DeuceToSeven => value,
+        HandRanking::ShortDeck {
+            trips_beat_straight,
+        } => short_deck_value(value, trips_beat_straight),
+    }
+}
+
+/// Converts a standard value into a short deck value by reordering the types of hands.
+fn short_deck_value(value: usize, trips_beat_straight: bool) -> usize {
+    let mut order = [
+        HIGH_CARD_OFFSET,
+        PAIR_OFFSET,
+        TWO_PAIR_OFFSET,
+        THREE_OFFSET,
+        STRAIGHT_OFFSET,
+        FULL_HOUSE_OFFSET,
+        FLUSH_OFFSET,
+        FOUR_OFFSET,
+        STRAIGHT_FLUSH_OFFSET,
+    ];
+    if trips_beat_straight {
+        order.swap(3, 4);
+    }
+    let offset = *order.iter().filter(|&&o| o <= value).max().unwrap();
+    let position = order.iter().position(|&o| o == offset).unwrap();
+    // Every type of hand has less than 13^5 values
+    position * 13_usize.pow(5) + value - offset
+}
+
+/// Returns the value of the best five cards out of at least five cards.
+pub fn value_of_best_five(cards: &[(u8, u8)], ranking: HandRanking) -> usize {
+    assert!(cards.len() >= 5);
+
+    let n = cards.len();
+    let mut best = 0;
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let hand = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+                        best = max(best, value_of_ranked_five(&hand, ranking));
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Returns the value of an Omaha hand.
+/// The best hand uses exactly two of the hole cards and exactly three of the board cards.
+pub fn value_of_omaha_hand(hole_cards: &[(u8, u8)], board: &[(u8, u8)]) -> usize {
+    assert!(hole_cards.len() >= 2);
+    assert_eq!(board.len(), 5);
+
+    let mut best = 0;
+    for hole in hole_cards.iter().combinations(2) {
+        for middle in board.iter().combinations(3) {
+            let hand = [*hole[0], *hole[1], *middle[0], *middle[1], *middle[2]];
+            best = max(best, value_of_five(&hand));
+        }
+    }
+    best
+}
+
+/// Returns the value of a hand of exactly five cards, ranked as a deuce-to-seven low hand.
+/// The worst high hand is the best low hand: straights and flushes count and the ace is the highest card.
+/// Higher values are better, i.e. 7-5-4-3-2 has the highest value.
+pub fn deuce_to_seven_value_of_five(cards: &[(u8, u8)]) -> usize {
+    // The best high hand is a straight flush with an ace
+    STRAIGHT_FLUSH_OFFSET + 12 - value_of_ranked_five(cards, HandRanking::DeuceToSeven)
+}
+
+/// Returns the value of a hand of exactly five cards, ranked as an ace-to-five low hand.
+/// Straights and flushes do not count, the ace is the lowest card and pairs are bad.
+/// Higher values are better, i.e. 5-4-3-2-A has the highest value.
+pub fn low_value_of_five(cards: &[(u8, u8)]) -> usize {
+    assert_eq!(cards.len(), 5);
+
+    low_value_of_cards(cards)
+}
+
+/// Returns the ace-to-five low value of up to five cards, e.g. the visible cards of a Razz hand.
+/// Only values of the same amount of cards are comparable.
+pub fn low_value_of_cards(cards: &[(u8, u8)]) -> usize {
+    assert!(!cards.is_empty() && cards.len() <= 5);
+
+    // Ranks with the ace as lowest card
+    let mut ranks = [0u8; 5];
+    for (rank, card) in ranks.iter_mut().zip(cards) {
+        *rank = ace_low_rank(card.1);
+    }
+    let ranks = &mut ranks[..cards.len()];
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+    // Ranks grouped by their occurrences, biggest groups first, then highest ranks first
+    let mut groups = [(0usize, 0u8); 5];
+    let mut group_count = 0;
+    for &rank in ranks.iter() {
+        if group_count > 0 && groups[group_count - 1].1 == rank {
+            groups[group_count - 1].0 += 1;
+        } else {
+            groups[group_count] = (1, rank);
+            group_count += 1;
+        }
+    }
+    let groups = &mut groups[..group_count];
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    let mut counts = [0usize; 5];
+    for (count, group) in counts.iter_mut().zip(groups.iter()) {
+        *count = group.0;
+    }
+
+    // Paired cards are worse, from a single pair up to four of a kind
+    let badness = match counts {
+        [4, ..] => 5,
+        [3, 2, ..] => 4,
+        [3, ..] => 3,
+        [2, 2, ..] => 2,
+        [2, ..] => 1,
+        _ => 0,
+    };
+    // Within the same type of hand, lower ranks are better
+    let mut order = [12u8; 5];
+    for (i, group) in groups.iter().enumerate() {
+        order[i] = 12 - group.1;
+    }
+    (5 - badness) * 13_usize.pow(5)
+        + calculate_value(0, order[0], order[1], order[2], order[3], order[4])
+}
+
+/// Returns the value of the best ace-to-five low hand out of at least five cards, as in Razz.
+pub fn low_value_of_best_five(cards: &[(u8, u8)]) -> usize {
+    assert!(cards.len() >= 5);
+
+    cards
+        .iter()
+        .combinations(5)
+        .map(|hand| {
+            let hand = [*hand[0], *hand[1], *hand[2], *hand[3], *hand[4]];
+            low_value_of_five(&hand)
+        })
+        .max()
+        .unwrap()
+}
+
+/// Returns the value of up to four cards, e.g. the visible cards of a Seven-Card Stud hand.
+/// Only pairs, two pairs, three and four of a kind count, straights and flushes are not possible.
+/// Only values of the same amount of cards are comparable.
+pub fn value_of_partial_hand(cards: &[(u8, u8)]) -> usize {
+    assert!(!cards.is_empty() && cards.len() <= 4);
+
+    let mut values = [0u8; 4];
+    for (value, card) in values.iter_mut().zip(cards) {
+        *value = card.1;
+    }
+    let values = &mut values[..cards.len()];
+    values.sort_unstable_by(|a, b| b.cmp(a));
+
+    // Values grouped by their occurrences, biggest groups first, then highest values first
+    let mut groups = [(0usize, 0u8); 4];
+    let mut group_count = 0;
+    for &value in values.iter() {
+        if group_count > 0 && groups[group_count - 1].1 == value {
+            groups[group_count - 1].0 += 1;
+        } else {
+            groups[group_count] = (1, value);
+            group_count += 1;
+        }
+    }
+    let groups = &mut groups[..group_count];
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let category = match (groups[0].0, group_count) {
+        (4, _) => 4,
+        (3, _) => 3,
+        (2, 2) if cards.len() == 4 => 2,
+        (2, _) => 1,
+        _ => 0,
+    };
+    let mut v = [0u8; 4];
+    for (i, group) in groups.iter().enumerate() {
+        v[i] = group.1;
+    }
+    category * 13_usize.pow(4) + calculate_value(0, 0, v[0], v[1], v[2], v[3])
+}
+
+/// Returns the value of the best Omaha low hand with an eight-or-better qualifier.
+/// The best low hand uses exactly two of the hole cards and exactly three of the board cards,
+/// which must be five different cards of eight or lower.
+/// Returns `None` if no low hand qualifies.
+pub fn value_of_omaha_low_hand(hole_cards: &[(u8, u8)], board: &[(u8, u8)]) -> Option<usize> {
+    assert!(hole_cards.len() >= 2);
+    assert_eq!(board.len(), 5);
+
+    let mut best = None;
+    for hole in hole_cards.iter().combinations(2) {
+        for middle in board.iter().combinations(3) {
+            let hand = [*hole[0], *hole[1], *middle[0], *middle[1], *middle[2]];
+            if is_eight_or_better(&hand) {
+                best = max(best, Some(low_value_of_five(&hand)));
+            }
+        }
+    }
+    best
+}
+
+/// Returns the value of the best low hand out of at least five cards with an eight-or-better qualifier,
+/// e.g. the seven cards of a Stud Hi-Lo hand.
+/// Returns `None` if no low hand qualifies.
+pub fn value_of_eight_or_better_low_hand(cards: &[(u8, u8)]) -> Option<usize> {
+    assert!(cards.len() >= 5);
+
+    cards
+        .iter()
+        .copied()
+        .combinations(5)
+        .filter(|hand| is_eight_or_better(hand))
+        .map(|hand| low_value_of_five(&hand))
+        .max()
+}
+
+/// Checks whether five cards are all different and not higher than eight.
+fn is_eight_or_better(cards: &[(u8, u8)]) -> bool {
+    let mut seen = [false; 13];
+    for card in cards {
+        let rank = ace_low_rank(card.1) as usize;
+        if rank > EIGHT_LOW_RANK || seen[rank] {
+            return false;
+        }
+        seen[rank] = true;
+    }
+    true
+}
+
+/// Converts a card value into a rank with the ace as lowest card, i.e. ace is 0, two is 1 and king is 12.
+fn ace_low_rank(value: u8) -> u8 {
+    (value + 1) % 13
+}
+
+/// The ace-low rank of an eight, the highest card of a qualifying low hand.
+const EIGHT_LOW_RANK: usize = 7;
+
+/// Calculates the value for internal representation.
+fn calculate_value(
+    classification: usize,
+    order4: u8,
+    order3: u8,
+    order2: u8,
+    order1: u8,
+    order0: u8,
+) -> usize {
+    classification
+        + (order4 as usize) * (13 * 13 * 13 * 13)
+        + (order3 as usize) * (13 * 13 * 13)
+        + (order2 as usize) * (13 * 13)
+        + (order1 as usize) * (13)
+        + (order0 as usize)
+}
+
+/// The base offset for values of hands with type high card.
+/// It represents the general base offset as it is the smallest hand.
+pub const HIGH_CARD_OFFSET: usize = 0;
+
+/// The base offset for values of hands with type pair.
+/// It represents the next number after all possible high card values.
+pub const PAIR_OFFSET: usize = HIGH_CARD_OFFSET + 13_usize.pow(5);
+
+/// The base offset for values of hands with type two pair.
+/// It represents the next number after all possible pair values.
+pub const TWO_PAIR_OFFSET: usize = PAIR_OFFSET + 13_usize.pow(4);
+
+/// The base offset for values of hands with type three.
+/// It represents the next number after all possible two pair values.
+pub const THREE_OFFSET: usize = TWO_PAIR_OFFSET + 13_usize.pow(3);
+
+/// The base offset for values of hands with type straight.
+/// It represents the next number after all possible three values.
+pub const STRAIGHT_OFFSET: usize = THREE_OFFSET + 13_usize.pow(3);
+
+/// The base offset for values of hands with type flush.
+/// It represents the next number after all possible straight values.
+pub const FLUSH_OFFSET: usize = STRAIGHT_OFFSET + 13_usize.pow(1);
+
+/// The base offset for values of hands with type full house.
+/// It represents the next number after all possible flush values.
+pub const FULL_HOUSE_OFFSET: usize = FLUSH_OFFSET + 13_usize.pow(5);
+
+/// The base offset for values of hands with type four.
+/// It represents the next number after all possible flush values.
+pub const FOUR_OFFSET: usize = FULL_HOUSE_OFFSET + 13_usize.pow(2);
+
+/// The base offset for values of hands with type straight flush.
+/// It represents the next number after all possible four values.
+pub const STRAIGHT_FLUSH_OFFSET: usize = FOUR_OFFSET + 13_usize.pow(2);
+
+#[cfg(test)]
+// The expected values spell out every digit of the encoding, including those of zero
+#[allow(clippy::identity_op, clippy::erasing_op)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    /// Parses the space separated cards into the tuples the evaluators take.
+    fn to_tuples(s: &str) -> Vec<(u8, u8)> {
+        s.split(" ")
+            .map(Card::try_from)
+            .map(Result::unwrap)
+            .map(|card| (card.color(), card.value()))
+            .collect()
+    }
+
+    /// Utility function for testing a string of a card combination.
+    fn test_combination(s: &str, expected: usize) {
+        let iter = s
+            .split(" ")
+            .map(Card::try_from)
+            .map(Result::unwrap)
+            .map(|card| (card.color(), card.value()));
+        for per in iter.permutations(7) {
+            let value = value_of_hand(&per);
+            assert_eq!(
+                expected, value,
+                "Failed: {:?}. Expected {}, got {}",
+                per, expected, value
+            );
+        }
+    }
+
+    /// Testing allowed card combinations.
+    #[test]
+    fn test_possible_card_combinations() {
+        let test_cases = vec![
+            // Normal cases
+            (
+                "A0 B1 C3 D6 A7 B9 CA",
+                HIGH_CARD_OFFSET + (28561 * 10) + (2197 * 9) + (169 * 7) + (13 * 6) + 3,
+            ),
+            (
+                "A9 B1 C3 D6 A7 B9 CA",
+                PAIR_OFFSET + (2197 * 9) + (169 * 10) + (13 * 7) + 6,
+            ),
+            (
+                "A9 B1 C3 D3 A7 B9 CA",
+                TWO_PAIR_OFFSET + (169 * 9) + (13 * 3) + 10,
+            ),
+            (
+                "A0 BB CB D6 A7 BB CA",
+                THREE_OFFSET + (169 * 11) + (13 * 10) + 7,
+            ),
+            ("AC B1 C2 D3 A4 B5 CA", STRAIGHT_OFFSET + 5),
+            (
+                "A0 B1 B3 B6 B7 B0 CA",
+                FLUSH_OFFSET + (28561 * 7) + (2197 * 6) + (169 * 3) + (13 * 1) + 0,
+            ),
+            ("A0 B0 C0 D6 A7 B9 C9", FULL_HOUSE_OFFSET + (13 * 0) + 9),
+            ("A0 B1 C6 D6 A7 A6 B6", FOUR_OFFSET + (13 * 6) + 7),
+            ("BB B1 C6 C7 C8 C9 CA", STRAIGHT_FLUSH_OFFSET + 10),
+            // Special cases
+            ("AC C0 A1 D2 A3 CA B1", STRAIGHT_OFFSET + 3), // also called bicycle or wheel
+        ];
+        test_cases
+            .iter()
+            .for_each(|tuple| test_combination(tuple.0, tuple.1));
+    }
+
+    /// Testing card combinations, which need more than one hand type to be considered.
+    #[test]
+    fn test_ambiguous_card_combinations() {
+        let test_cases = [
+            // Two threes are a full house
+            ("A0 B0 C0 A5 B5 C5 D9", FULL_HOUSE_OFFSET + (13 * 5) + 0),
+            // The highest pair completes the full house
+            ("A0 B0 C0 A5 B5 C9 D9", FULL_HOUSE_OFFSET + (13 * 0) + 9),
+            ("A0 B0 C0 A1 A2 A3 B3", FULL_HOUSE_OFFSET + (13 * 0) + 3),
+            // Straight flush with a pair in between
+            ("A3 A4 B4 A5 A6 A7 C0", STRAIGHT_FLUSH_OFFSET + 7),
+            // Straight flush with the ace as one
+            ("AC A0 A1 A2 A3 B5 C9", STRAIGHT_FLUSH_OFFSET + 3),
+        ];
+        test_cases
+            .iter()
+            .for_each(|tuple| test_combination(tuple.0, tuple.1));
+    }
+
+    /// Testing that Omaha hands use exactly two hole cards and three board cards.
+    #[test]
+    fn test_omaha_hands() {
+        let test_cases = vec![
+            // Straight on the board does not count, only a pair of aces
+            (
+                "AC BC CC DC",
+                "A0 B1 C2 D3 A4",
+                PAIR_OFFSET + (2197 * 12) + (169 * 4) + (13 * 3) + 2,
+            ),
+            // Flush with exactly two hole cards of the suit
+            (
+                "AC A0 BB CB",
+                "A5 A7 A9 D1 D2",
+                FLUSH_OFFSET + (28561 * 12) + (2197 * 9) + (169 * 7) + (13 * 5) + 0,
+            ),
+            // A single hole card of the suit is no flush
+            (
+                "AC BB CB DA",
+                "A5 A7 A9 A1 D2",
+                PAIR_OFFSET + (2197 * 11) + (169 * 9) + (13 * 7) + 5,
+            ),
+        ];
+        for (hole_cards, board, expected) in test_cases {
+            let value = value_of_omaha_hand(&to_tuples(hole_cards), &to_tuples(board));
+            assert_eq!(
+                expected, value,
+                "Failed: {} {}. Expected {}, got {}",
+                hole_cards, board, expected, value
+            );
+        }
+    }
+
+    /// Testing ace-to-five low hands with the eight-or-better qualifier.
+    #[test]
+    fn test_omaha_low_hands() {
+        // Best to worst low hands: the wheel, a six low, an eight low, a pair, two pairs
+        let ordered = [
+            "AC B0 C1 D2 A3",
+            "AC B0 C1 D2 A4",
+            "A2 B3 C4 D5 A6",
+            "AC BC C0 D1 A2",
+            "AC BC C0 D0 A2",
+        ];
+        for pair in ordered.windows(2) {
+            let (better, worse) = (
+                low_value_of_five(&to_tuples(pair[0])),
+                low_value_of_five(&to_tuples(pair[1])),
+            );
+            assert!(
+                better > worse,
+                "Failed: {} should be better than {}",
+                pair[0],
+                pair[1]
+            );
+        }
+
+        let test_cases = vec![
+            // Only two low cards on the board
+            ("AC B0 C1 D2", "A9 B1 C9 DA A8", None),
+            // No two low hole cards
+            ("AB BB CA D9", "A0 B1 C2 D9 A8", None),
+            // A paired hole card does not help, exactly two hole cards and three board cards count
+            ("AC B0 C0 D0", "A3 B4 C6 D9 A8", Some("AC B0 A3 B4 C6")),
+            // Straights and flushes do not matter
+            ("AC A0 BB CB", "A1 A2 A3 D9 D8", Some("AC A0 A1 A2 A3")),
+        ];
+        for (hole_cards, board, expected) in test_cases {
+            let expected = expected.map(|hand| low_value_of_five(&to_tuples(hand)));
+            let value = value_of_omaha_low_hand(&to_tuples(hole_cards), &to_tuples(board));
+            assert_eq!(
+                expected, value,
+                "Failed: {} {}. Expected {:?}, got {:?}",
+                hole_cards, board, expected, value
+            );
+        }
+    }
+
+    /// Testing the evaluation of stud hands: razz low hands of seven cards and incomplete face up cards.
+    #[test]
+    fn test_stud_hands() {
+        // Best to worst low face up cards: a single ace, a pair, two pairs, trips, a full house, quads
+        let ordered = [
+            "AC",
+            "AC B0 C5",
+            "A3 B3",
+            "A3 B3 C4 D4",
+            "A3 B3 C3",
+            "A3 B3 C3 A4 B4",
+            "A3 B3 C3 D3",
+        ];
+        for pair in ordered.windows(2) {
+            let (better, worse) = (
+                low_value_of_cards(&to_tuples(pair[0])),
+                low_value_of_cards(&to_tuples(pair[1])),
+            );
+            assert!(
+                better > worse,
+                "Failed: {} should be better than {}",
+                pair[0],
+                pair[1]
+            );
+        }
+
+        // Best to worst high face up cards: a pair, an ace, a king
+        let ordered = ["A0 B0", "AC B0", "AB BA", "AB"];
+        for pair in ordered.windows(2) {
+            let (better, worse) = (
+                value_of_partial_hand(&to_tuples(pair[0])),
+                value_of_partial_hand(&to_tuples(pair[1])),
+            );
+            assert!(
+                better > worse,
+                "Failed: {} should be better than {}",
+                pair[0],
+                pair[1]
+            );
+        }
+
+        let test_cases = vec![
+            // Pairs are avoided, straights and flushes do not matter
+            ("AC B0 C1 D2 A3 B3 C3", "AC B0 C1 D2 A3"),
+            ("A0 A1 A2 A3 A4 B0 B1", "A0 A1 A2 A3 A4"),
+            // With only four different values, the lowest pair is played
+            ("AC B0 C1 D2 BC C0 D1", "AC B0 C1 D2 BC"),
+        ];
+        for (cards, expected) in test_cases {
+            let expected_value = low_value_of_five(&to_tuples(expected));
+            let value = low_value_of_best_five(&to_tuples(cards));
+            assert_eq!(
+                expected_value, value,
+                "Failed: {}. Expected {}, got {}",
+                cards, expected, value
+            );
+        }
+
+        let test_cases = vec![
+            ("AC B0 C1 D2 A6 BB CA", Some("AC B0 C1 D2 A6")),
+            // Only three different low cards
+            ("AC B0 C1 D0 A1 BB CA", None),
+            // A nine is too high
+            ("AC B0 C1 D2 A7 BB CA", None),
+        ];
+        for (cards, expected) in test_cases {
+            let expected_value = expected.map(|hand| low_value_of_five(&to_tuples(hand)));
+            let value = value_of_eight_or_better_low_hand(&to_tuples(cards));
+            assert_eq!(
+                expected_value, value,
+                "Failed: {}. Expected {:?}, got {:?}",
+                cards, expected, value
+            );
+        }
+    }
+
+    /// Testing the ordering of deuce-to-seven low hands.
+    #[test]
+    fn test_deuce_to_seven_hands() {
+        // Best to worst: the number one, an eight low, a wheel is only an ace high, a pair, a straight, a flush
+        let ordered = [
+            "A5 B3 C2 D1 A0",
+            "A6 B3 C2 D1 A0",
+            "AC B3 C2 D1 A0",
+            "A0 B0 C3 D4 A5",
+            "A5 B4 C3 D2 A1",
+            "A5 A3 A2 A1 A0",
+        ];
+        for pair in ordered.windows(2) {
+            let (better, worse) = (
+                deuce_to_seven_value_of_five(&to_tuples(pair[0])),
+                deuce_to_seven_value_of_five(&to_tuples(pair[1])),
+            );
+            assert!(
+                better > worse,
+                "Failed: {} should be better than {}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    /// Testing the ordering of short deck hands.
+    #[test]
+    fn test_short_deck_hands() {
+        let trips = "A4 B4 C4 D7 A8 BA CB";
+        let lowest_straight = "AC B4 C5 D6 A7 BA CB";
+        let straight = "A4 B5 C6 D7 A8 BA CB";
+        let full_house = "A4 B4 C4 D7 A7 BA CB";
+        let flush = "A4 A6 A8 AA AB B5 C7";
+        let four = "A4 B4 C4 D4 A7 BA CB";
+        let straight_flush = "AC A4 A5 A6 A7 BA CB";
+
+        // Worst to best hands for both rankings
+        let test_cases = [
+            (
+                false,
+                [
+                    trips,
+                    lowest_straight,
+                    straight,
+                    full_house,
+                    flush,
+                    four,
+                    straight_flush,
+                ],
+            ),
+            (
+                true,
+                [
+                    lowest_straight,
+                    straight,
+                    trips,
+                    full_house,
+                    flush,
+                    four,
+                    straight_flush,
+                ],
+            ),
+        ];
+        for (trips_beat_straight, ordered) in test_cases {
+            for pair in ordered.windows(2) {
+                let worse = value_of_short_deck_hand(&to_tuples(pair[0]), trips_beat_straight);
+                let better = value_of_short_deck_hand(&to_tuples(pair[1]), trips_beat_straight);
+                assert!(
+                    better > worse,
+                    "Failed: {} should be better than {} ({})",
+                    pair[1],
+                    pair[0],
+                    trips_beat_straight
+                );
+            }
+        }
+
+        // A-6-7-8-9 is no straight with a full deck
+        assert!(value_of_hand(&to_tuples(lowest_straight)) < value_of_hand(&to_tuples(trips)));
+    }
+
+    /// Testing failure of illegal cards.
+    #[test]
+    fn test_invalid_cards() {
+        let test_cases = vec!["A23", "A", "AD", "12", "  "];
+        for case in test_cases {
+            assert!(
+                Card::try_from(case).is_err(),
+                "Failed: {}. Expected Error, got {}",
+                case,
+                Card::try_from(case).unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,3611 @@
+use crate::betting::{BetRange, BettingSituation, BettingStructure, StraddleRule};
+use crate::cards::Card;
+use crate::equity::{self, DEFAULT_ITERATIONS};
+use crate::hand_log::{ForcedBet, HandEvent, Move};
+use crate::raw_message::{PlayerChoice, RawMessage};
+use crate::rotation::Rotation;
+use crate::shuffle::{self, Seed};
+use crate::variant::GameVariant;
+use itertools::Itertools;
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Time for a player to bet. Currently very high, to facilitate testing.
+const BET_TIMEOUT: Duration = Duration::from_secs(10000);
+
+/// Time for the players to look at the equities after each street of an all-in run-out.
+const ALL_IN_STREET_DELAY: Duration = Duration::from_secs(3);
+
+/// Time for the players to agree on how many times the board is run out.
+const RUN_IT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time for the players to choose the hole card they discard.
+const DISCARD_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Time for the players to choose the cards they exchange in draw games.
+const DRAW_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Time for the player on the button to choose the variant in dealer's choice.
+const VARIANT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Time for a player to decide whether to straddle.
+const STRADDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time for the players to add entropy to an auditable shuffle.
+const ENTROPY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Time for the players to vote for a bomb pot.
+const BOMB_POT_VOTE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time for the players to look at the cards at the showdown before the pot is paid.
+const SHOWDOWN_DELAY: Duration = Duration::from_secs(10);
+
+/// Time for the players to look at the stats after everyone else folded.
+const EARLY_END_DELAY: Duration = Duration::from_secs(7);
+
+/// The settings of a table, which stay the same for the whole game,
+/// except for the variant and betting structure of rotating tables.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableSettings {
+    /// The variant of poker played at the table.
+    pub variant: GameVariant,
+
+    /// The rules which limit how much a player may bet.
+    pub betting: BettingStructure,
+
+    /// How the variant changes between hands.
+    pub rotation: Rotation,
+
+    /// Which straddles players may post before the cards are dealt.
+    pub straddles: StraddleRule,
+
+    /// The small blind of the first round.
+    pub small_blind: usize,
+
+    /// The amount of coins every player starts with.
+    pub start_money: usize,
+
+    /// The maximal amount of boards players can agree to deal once they are all in.
+    pub max_run_outs: usize,
+
+    /// If set, players may reveal the undealt board after everyone else folded,
+    /// but at most once every that many hands.
+    pub rabbit_hunt_interval: Option<usize>,
+
+    /// If set, some hands are bomb pots.
+    pub bomb_pot: Option<BombPotSettings>,
+
+    /// Whether every deck is shuffled with the commit-reveal scheme of `shuffle`,
+    /// to which the players can add entropy.
+    pub is_shuffle_audited: bool,
+}
+
+/// The settings of bomb pots: hands in which every player antes and the betting starts on the flop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BombPotSettings {
+    /// The amount every player pays instead of the blinds.
+    pub ante: usize,
+
+    /// If set, every hand whose number is a multiple of it is a bomb pot.
+    pub interval: Option<usize>,
+
+    /// Whether the players vote before every other hand, where a bomb pot needs the votes of everyone.
+    pub is_voted: bool,
+
+    /// Whether the hand is played on two boards, each of which pays half of the pot.
+    pub is_double_board: bool,
+}
+
+/// The state of a table between two hands, from which a game is continued after a restart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableState {
+    /// The settings of the table, with the variant of the last hand.
+    pub settings: TableSettings,
+
+    /// The seed of the table.
+    pub seed: u64,
+
+    /// The amount of hands dealt so far.
+    pub hand_number: usize,
+
+    /// The amount of hands played with the current variant.
+    pub hands_with_variant: usize,
+
+    pub dealer: usize,
+    pub small_blind: usize,
+    pub seats: Vec<SeatState>,
+}
+
+/// What the table remembers about a player between two hands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeatState {
+    pub coins: usize,
+    pub is_out: bool,
+
+    /// The hand number of the last rabbit hunt the player requested.
+    pub last_rabbit_hunt: Option<usize>,
+}
+
+/// The state of a player at the table, as far as the rules are concerned.
+#[derive(Clone, Debug, Default)]
+pub struct Seat {
+    pub cards: Vec<Card>,
+
+    /// The cards of `cards` which were dealt face up in stud games.
+    pub up_cards: Vec<Card>,
+
+    pub has_folded: bool,
+    coins: usize,
+    pub is_out: bool,
+    current_betting_amount: usize,
+
+    /// The value of the cards when the player wants to show his cards.
+    pub end_of_round_values: Option<usize>,
+
+    /// The expected share of the pot in percent during an all-in run-out.
+    /// The cards of the player are revealed while it is set.
+    pub equity: Option<f64>,
+
+    /// The hand number of the last rabbit hunt the player requested.
+    pub last_rabbit_hunt: Option<usize>,
+
+    /// Whether the player is still at the table. Players who left go all in when they have to bet.
+    pub is_connected: bool,
+
+    /// Whether the player did not return to a restored table, which keeps the seat out of every hand.
+    pub is_sitting_out: bool,
+}
+
+impl Seat {
+    /// Determines the value of the hand according to the rules of the variant.
+    pub fn determine_card_value(&self, variant: GameVariant, table_cards: &[Card]) -> usize {
+        variant.value_of_hand(&self.cards, table_cards)
+    }
+
+    /// Obtain this `Seat`'s amount of coins.
+    pub fn coins(&self) -> usize {
+        self.coins
+    }
+
+    /// Obtain this `Seat`'s current betting amount.
+    pub fn current_betting_amount(&self) -> usize {
+        self.current_betting_amount
+    }
+
+    /// Subtracts the betting amount (but not more than max_amount) and returns it.
+    /// If any money is left over it is added back to the `Seat`'s coins.
+    /// Resets the betting amount to zero.
+    fn take_betting_amount(&mut self, max_amount: usize) -> usize {
+        let amount_deduced = std::cmp::min(self.current_betting_amount, max_amount);
+        self.current_betting_amount -= amount_deduced;
+        self.coins += self.current_betting_amount;
+        self.current_betting_amount = 0;
+        amount_deduced
+    }
+
+    /// Add a specified amount of coins to the `Seat`'s balance.
+    fn add_coins(&mut self, amount: usize) {
+        self.coins += amount;
+    }
+
+    /// Deduct money from the `Seat`'s balance for betting.
+    fn deduct_from_money_to_bet(&mut self, amount: usize) {
+        self.coins += self.current_betting_amount;
+        assert!(self.coins >= amount);
+        self.coins -= amount;
+        self.current_betting_amount = amount;
+    }
+}
+
+/// Everything the players can do at the table, in the order they do it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// A player made a choice, which only counts if the engine waits for it.
+    Choice { player: usize, choice: PlayerChoice },
+
+    /// A player wants the board to be run out that many times.
+    RunItTimes { player: usize, times: usize },
+
+    /// A player wants to see the cards which would have been dealt.
+    RabbitHunt { player: usize },
+
+    /// A player adds entropy to the auditable shuffle of the hand.
+    ClientSeed { player: usize, seed: String },
+
+    /// A player left the table.
+    Disconnect { player: usize },
+
+    /// The time of the last `Event::Wait` passed.
+    Timeout,
+}
+
+impl Action {
+    /// Returns the action the message of a player asks for, if any.
+    pub fn from_message(player: usize, message: RawMessage) -> Option<Self> {
+        match message {
+            RawMessage::PlayerChoice(choice) => Some(Self::Choice { player, choice }),
+            RawMessage::RunItTimes(times) => Some(Self::RunItTimes { player, times }),
+            RawMessage::RabbitHunt => Some(Self::RabbitHunt { player }),
+            RawMessage::ClientSeed(seed) => Some(Self::ClientSeed { player, seed }),
+            RawMessage::ConnectionEnded => Some(Self::Disconnect { player }),
+            _ => None,
+        }
+    }
+
+    /// Returns the player and the message which ask for the action, unless it is a timeout.
+    pub fn to_message(&self) -> Option<(usize, RawMessage)> {
+        match self.clone() {
+            Self::Choice { player, choice } => Some((player, RawMessage::PlayerChoice(choice))),
+            Self::RunItTimes { player, times } => Some((player, RawMessage::RunItTimes(times))),
+            Self::RabbitHunt { player } => Some((player, RawMessage::RabbitHunt)),
+            Self::ClientSeed { player, seed } => Some((player, RawMessage::ClientSeed(seed))),
+            Self::Disconnect { player } => Some((player, RawMessage::ConnectionEnded)),
+            Self::Timeout => None,
+        }
+    }
+}
+
+/// Everything the engine wants the players to know or do.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The state of the table changed and should be shown to the players.
+    StateChanged {
+        hand_winner: Option<usize>,
+        is_showdown: bool,
+    },
+
+    /// A player has to bet an amount of the range.
+    AwaitingBet { player: usize, range: BetRange },
+
+    /// The players have to discard a hole card.
+    AwaitingDiscard { players: Vec<usize> },
+
+    /// The players may exchange any of their cards.
+    AwaitingDraw { players: Vec<usize> },
+
+    /// The player on the button has to choose the variant of the hand.
+    AwaitingVariant {
+        player: usize,
+        choices: Vec<GameVariant>,
+    },
+
+    /// A player may straddle the amount.
+    AwaitingStraddle { player: usize, amount: usize },
+
+    /// The players may vote for a bomb pot.
+    AwaitingBombPotVote { players: Vec<usize> },
+
+    /// The players may agree to run the board out up to `max_run_outs` times.
+    AwaitingRunOuts {
+        players: Vec<usize>,
+        max_run_outs: usize,
+    },
+
+    /// The order of the deck was committed to, the players may add entropy.
+    ShuffleCommitted {
+        hand_number: usize,
+        commitment: String,
+    },
+
+    /// The hand ended, so the server seed of its shuffle and the seeds of the players are revealed.
+    ShuffleRevealed {
+        hand_number: usize,
+        server_seed: String,
+        client_seeds: Vec<Option<String>>,
+    },
+
+    /// The cards a rabbit hunt revealed, which are shown to all players.
+    RabbitHuntResult {
+        requested_by: usize,
+        cards: Vec<Card>,
+    },
+
+    /// Something happened in the hand, which belongs in the hand log but is not sent to the players.
+    Logged(HandEvent),
+
+    /// The next hand is about to start from this state, from which the game can be restored.
+    Checkpoint(TableState),
+
+    /// The engine waits that long for the players, afterwards `Action::Timeout` has to be applied.
+    Wait(Duration),
+
+    /// The game ended with the winner, if there is one.
+    GameEnd(Option<usize>),
+}
+
+/// What the engine waits for.
+#[derive(Clone, Debug)]
+enum Phase {
+    /// The game has not started yet or has ended.
+    Idle,
+    ChoosingVariant,
+    CollectingEntropy {
+        pending: Vec<usize>,
+    },
+    VotingBombPot {
+        pending: Vec<usize>,
+    },
+    Straddling {
+        position: usize,
+        amount: usize,
+        is_button: bool,
+    },
+    Betting {
+        index: usize,
+        raises: usize,
+    },
+    Adjusting {
+        index: usize,
+    },
+    Discarding {
+        pending: Vec<usize>,
+    },
+    AgreeingRunOuts {
+        pending: Vec<usize>,
+        run_outs: usize,
+    },
+    Drawing {
+        pending: Vec<usize>,
+        draws: Vec<(usize, Vec<Card>)>,
+    },
+    RabbitHunting {
+        is_revealed: bool,
+    },
+    /// Lets the players look at the table, then continues with the step.
+    Pausing(Step),
+}
+
+/// The steps of a round which follow a pause.
+#[derive(Clone, Debug)]
+enum Step {
+    AfterEarlyEnd,
+    DealStreet,
+    Payout(Vec<BoardWinners>),
+    NextRound,
+}
+
+/// The rules of a game of Poker, in one of the variants of `GameVariant`.
+/// The engine knows nothing about the network: every `Action` of the players is applied
+/// and answered with the `Event`s the players should know about.
+///
+/// Round numbers as following:
+///
+/// * Pre round 0: give cards, prepare round
+///
+/// * Round 0: betting
+///
+/// * Round 1: adjust bets
+///
+/// * End of round 1: show 3 cards
+///
+/// * Round 2: betting
+///
+/// * Round 3: adjust betting
+///
+/// * End of round 3: show 4 cards
+///
+/// * Round 4: betting
+///
+/// * Round 5: adjust betting
+///
+/// * End of round 5: show 5 cards
+///
+/// * Round 6: betting
+///
+/// * Round 7: adjust betting
+///
+/// Stud games have no board: players are dealt three cards before round 0, one of them face up,
+/// and one more card at the end of rounds 1, 3, 5 and 7, the last one face down.
+/// Their fifth street is played in rounds 8 and 9.
+///
+/// Draw games have no board either: instead of showing cards at the end of rounds 1, 3 and 5,
+/// every player may exchange any of his cards.
+///
+/// In bomb pots, every player antes before round 0 and rounds 0 and 1 are skipped.
+///
+/// * End of round 7: calculate winner and set round to 0
+pub struct Engine {
+    settings: TableSettings,
+    seats: Vec<Seat>,
+    open_middle_cards: Vec<Card>,
+
+    /// Additional boards if the players agreed to run the board out more than once.
+    /// They share the cards of `open_middle_cards` that were open when the players went all in.
+    extra_boards: Vec<Vec<Card>>,
+
+    card_stack: Vec<Card>,
+
+    /// Cards which were exchanged in draw games. They are reshuffled once the card stack runs out.
+    discarded_cards: Vec<Card>,
+
+    round_number: usize,
+
+    /// Players (in order) who play in this round.
+    /// Ideally should be connected (but may be not) and should not have folded or be out.
+    players_in_round: Vec<usize>,
+
+    /// Players who were dealt into the current hand, even if they folded since.
+    hand_players: Vec<usize>,
+
+    /// Starting position is one after the dealer.
+    dealer: usize,
+
+    small_blind: usize,
+
+    /// The amount of hands dealt so far.
+    hand_number: usize,
+
+    /// The amount of hands played with the current variant.
+    hands_with_variant: usize,
+
+    /// The amount of straddles posted in this hand, which count as raises before the flop.
+    straddles: usize,
+
+    /// Whether this hand is a bomb pot, which skips the betting before the flop.
+    is_bomb_pot: bool,
+
+    /// Whether the current street is dealt without betting, as the players are all in.
+    is_running_out: bool,
+
+    /// The winners of every board, while the showdown is shown to the players.
+    showdown_winners: Vec<BoardWinners>,
+
+    phase: Phase,
+
+    /// The seed of the table, from which the seed of every hand is derived.
+    seed: u64,
+
+    /// The secret of the table, from which the server seeds of auditable shuffles are derived.
+    /// Tables with an auditable shuffle need one, see `with_secret`.
+    secret: Option<Seed>,
+
+    /// The server seed of the current hand, until it is revealed.
+    server_seed: Option<Seed>,
+
+    /// The server seeds of the audited hands of a recorded game, with which a replay shuffles them.
+    revealed_seeds: HashMap<usize, Seed>,
+
+    /// The entropy every player added to the shuffle of the current hand.
+    client_seeds: Vec<Option<String>>,
+
+    /// The generator of the current hand, seeded with its hand seed.
+    rng: StdRng,
+}
+
+/// The winners of the pot of a single board.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoardWinners {
+    /// Players who win the high half (or the whole pot, if there is no low), in playing order.
+    /// Empty if the pot is returned to the players.
+    pub high: Vec<usize>,
+
+    /// Players who win the low half of the pot, in playing order. Empty if there is no qualifying low.
+    pub low: Vec<usize>,
+}
+
+impl Engine {
+    /// Creates the engine of a table with the amount of players.
+    /// Every hand is shuffled by a generator seeded with `hand_seed(seed, hand_number)`,
+    /// so equal seeds and actions lead to equal games.
+    pub fn new(settings: TableSettings, players: usize, seed: u64) -> Self {
+        Self {
+            settings,
+            seats: vec![
+                Seat {
+                    is_connected: true,
+                    ..Seat::default()
+                };
+                players
+            ],
+            open_middle_cards: vec![],
+            extra_boards: vec![],
+            card_stack: vec![],
+            discarded_cards: vec![],
+            round_number: 0,
+            players_in_round: vec![],
+            hand_players: vec![],
+            dealer: 0,
+            small_blind: 0,
+            hand_number: 0,
+            hands_with_variant: 0,
+            straddles: 0,
+            is_bomb_pot: false,
+            is_running_out: false,
+            showdown_winners: vec![],
+            phase: Phase::Idle,
+            seed,
+            secret: None,
+            server_seed: None,
+            revealed_seeds: HashMap::new(),
+            client_seeds: vec![],
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Restores the engine of a table from its state between two hands.
+    /// The game continues with the next hand once it is resumed.
+    pub fn restore(state: TableState) -> Self {
+        let mut engine = Self::new(state.settings, state.seats.len(), state.seed);
+        engine.hand_number = state.hand_number;
+        engine.hands_with_variant = state.hands_with_variant;
+        engine.dealer = state.dealer;
+        engine.small_blind = state.small_blind;
+        for (seat, state) in engine.seats.iter_mut().zip(state.seats) {
+            seat.coins = state.coins;
+            seat.is_out = state.is_out;
+            seat.last_rabbit_hunt = state.last_rabbit_hunt;
+        }
+        engine
+    }
+
+    /// Sets the secret from which the server seeds of auditable shuffles are derived, which they require.
+    /// Only a secret which can not be guessed keeps the deals secret until they are revealed,
+    /// so it never follows from the seed of the table.
+    pub fn with_secret(mut self, secret: Seed) -> Self {
+        self.secret = Some(secret);
+        self
+    }
+
+    /// Shuffles the audited hands with the server seeds which were revealed after them, by hand number,
+    /// instead of deriving the server seeds from the secret. This lets a replay deal the recorded cards.
+    pub fn with_revealed_seeds(mut self, revealed_seeds: HashMap<usize, Seed>) -> Self {
+        self.revealed_seeds = revealed_seeds;
+        self
+    }
+
+    /// Returns the seed of the table.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the amount of hands dealt so far, which is the number of the current hand.
+    pub fn hand_number(&self) -> usize {
+        self.hand_number
+    }
+
+    /// Returns the settings of the table, with the variant of the current hand.
+    pub fn settings(&self) -> &TableSettings {
+        &self.settings
+    }
+
+    /// Returns the state of every player.
+    pub fn seats(&self) -> &[Seat] {
+        &self.seats
+    }
+
+    /// Returns the open cards of the first board.
+    pub fn open_middle_cards(&self) -> &[Card] {
+        &self.open_middle_cards
+    }
+
+    /// Returns all boards but the first.
+    pub fn extra_boards(&self) -> &[Vec<Card>] {
+        &self.extra_boards
+    }
+
+    /// Returns the number of the current round.
+    pub fn round_number(&self) -> usize {
+        self.round_number
+    }
+
+    /// Returns whether this hand is a bomb pot.
+    pub fn is_bomb_pot(&self) -> bool {
+        self.is_bomb_pot
+    }
+
+    /// Returns the winners of every board while the showdown is shown.
+    pub fn showdown_winners(&self) -> &[BoardWinners] {
+        &self.showdown_winners
+    }
+
+    /// Returns the next variant and the amount of hands until it is played, if it is known in advance.
+    pub fn next_variant_change(&self) -> Option<(GameVariant, usize)> {
+        self.settings
+            .rotation
+            .scheduled_change(self.settings.variant, self.hands_with_variant)
+    }
+
+    /// Starts the game by giving the players coins and starting the first hand.
+    pub fn start(&mut self) -> Vec<Event> {
+        let mut events = vec![];
+        self.small_blind = self.settings.small_blind;
+        for seat in &mut self.seats {
+            seat.coins = self.settings.start_money;
+        }
+        self.start_round(&mut events);
+        events
+    }
+
+    /// Continues a restored game by starting its next hand.
+    /// The absent players did not return to the table and are treated as having left it,
+    /// so they are not dealt in and their stacks are not put at risk.
+    pub fn resume(&mut self, absent: &[usize]) -> Vec<Event> {
+        for &player in absent {
+            self.seats[player].is_connected = false;
+            self.seats[player].is_sitting_out = true;
+        }
+        let mut events = vec![];
+        self.start_round(&mut events);
+        events
+    }
+
+    /// Returns the state of the table, which is all there is to the game while no hand is played.
+    pub fn state(&self) -> TableState {
+        TableState {
+            settings: self.settings.clone(),
+            seed: self.seed,
+            hand_number: self.hand_number,
+            hands_with_variant: self.hands_with_variant,
+            dealer: self.dealer,
+            small_blind: self.small_blind,
+            seats: self
+                .seats
+                .iter()
+                .map(|seat| SeatState {
+                    coins: seat.coins,
+                    is_out: seat.is_out,
+                    last_rabbit_hunt: seat.last_rabbit_hunt,
+                })
+                .collect(),
+        }
+    }
+
+    /// Applies the action of a player (or the passing of time) and returns what happened.
+    /// Actions the engine does not wait for are ignored.
+    pub fn apply(&mut self, action: Action) -> Vec<Event> {
+        let mut events = vec![];
+        if let Action::Disconnect { player } = action {
+            self.seats[player].is_connected = false;
+        }
+        let phase = std::mem::replace(&mut self.phase, Phase::Idle);
+        match (phase, action) {
+            (Phase::ChoosingVariant, Action::Choice { player, choice })
+                if player == self.dealer =>
+            {
+                let choices = self.settings.rotation.choices();
+                let variant = match choice {
+                    PlayerChoice::ChooseVariant(name) => GameVariant::try_from(name.as_str()).ok(),
+                    _ => None,
+                };
+                match variant.filter(|v| choices.contains(v)) {
+                    Some(variant) => self.start_hand(&mut events, Some(variant)),
+                    None => self.phase = Phase::ChoosingVariant,
+                }
+            }
+            (Phase::ChoosingVariant, Action::Disconnect { player }) if player == self.dealer => {
+                self.start_hand(&mut events, None)
+            }
+            (Phase::ChoosingVariant, Action::Timeout) => self.start_hand(&mut events, None),
+
+            (Phase::CollectingEntropy { mut pending }, action) => {
+                match action {
+                    Action::ClientSeed { player, seed }
+                        if pending.contains(&player)
+                            && seed.len() <= shuffle::MAX_CLIENT_SEED_LENGTH =>
+                    {
+                        self.client_seeds[player] = Some(seed);
+                        pending.retain(|i| *i != player);
+                    }
+                    // Wait no longer
+                    Action::Disconnect { player } => pending.retain(|i| *i != player),
+                    Action::Timeout => pending.clear(),
+                    _ => { /* Ignore all other actions */ }
+                }
+                if pending.is_empty() {
+                    self.finish_audited_shuffle(&mut events);
+                } else {
+                    self.phase = Phase::CollectingEntropy { pending };
+                }
+            }
+
+            (Phase::VotingBombPot { mut pending }, Action::Choice { player, choice })
+                if pending.contains(&player) =>
+            {
+                match choice {
+                    PlayerChoice::BombPot(true) => {
+                        pending.retain(|i| *i != player);
+                        if pending.is_empty() {
+                            self.post_forced_bets(&mut events, true);
+                        } else {
+                            self.phase = Phase::VotingBombPot { pending };
+                        }
+                    }
+                    // One vote against it is enough
+                    PlayerChoice::BombPot(false) => self.post_forced_bets(&mut events, false),
+                    _ => self.phase = Phase::VotingBombPot { pending },
+                }
+            }
+            (Phase::VotingBombPot { pending }, Action::Disconnect { player })
+                if pending.contains(&player) =>
+            {
+                self.post_forced_bets(&mut events, false)
+            }
+            (Phase::VotingBombPot { .. }, Action::Timeout) => {
+                self.post_forced_bets(&mut events, false)
+            }
+
+            (
+                Phase::Straddling {
+                    position,
+                    amount,
+                    is_button,
+                },
+                action,
+            ) => {
+                let straddler = self.players_in_round[position];
+                let is_straddling = match action {
+                    Action::Choice {
+                        player,
+                        choice: PlayerChoice::Straddle(is_straddling),
+                    } if player == straddler => Some(is_straddling),
+                    Action::Disconnect { player } if player == straddler => Some(false),
+                    Action::Timeout => Some(false),
+                    _ => None,
+                };
+                match is_straddling {
+                    Some(is_straddling) => self.continue_straddles(
+                        &mut events,
+                        position,
+                        amount,
+                        is_button,
+                        is_straddling,
+                    ),
+                    None => {
+                        self.phase = Phase::Straddling {
+                            position,
+                            amount,
+                            is_button,
+                        }
+                    }
+                }
+            }
+
+            (Phase::Betting { index, raises }, action) => {
+                let bettor = self.players_in_round[index];
+                match action {
+                    Action::Choice { player, choice } if player == bettor => {
+                        self.resolve_bet(&mut events, index, raises, Some(choice))
+                    }
+                    Action::Disconnect { player } if player == bettor => {
+                        self.resolve_bet(&mut events, index, raises, None)
+                    }
+                    Action::Timeout => self.resolve_bet(&mut events, index, raises, None),
+                    _ => self.phase = Phase::Betting { index, raises },
+                }
+            }
+
+            (Phase::Adjusting { index }, action) => {
+                let bettor = self.players_in_round[index];
+                match action {
+                    Action::Choice { player, choice } if player == bettor => {
+                        self.resolve_adjustment(&mut events, index, Some(choice))
+                    }
+                    Action::Disconnect { player } if player == bettor => {
+                        self.resolve_adjustment(&mut events, index, None)
+                    }
+                    Action::Timeout => self.resolve_adjustment(&mut events, index, None),
+                    _ => self.phase = Phase::Adjusting { index },
+                }
+            }
+
+            (Phase::Discarding { mut pending }, action) => {
+                match action {
+                    Action::Choice {
+                        player,
+                        choice: PlayerChoice::Discard(card),
+                    } if pending.contains(&player) => {
+                        let seat = &mut self.seats[player];
+                        if let Some(index) = seat.cards.iter().position(|c| *c == card) {
+                            seat.cards.remove(index);
+                            pending.retain(|i| *i != player);
+                            events.push(Event::Logged(HandEvent::CardDiscarded { player, card }));
+                        }
+                    }
+                    // Wait no longer
+                    Action::Disconnect { player } => pending.retain(|i| *i != player),
+                    Action::Timeout => pending.clear(),
+                    _ => { /* Ignore all other actions */ }
+                }
+                if pending.is_empty() {
+                    self.finish_discard(&mut events);
+                } else {
+                    self.phase = Phase::Discarding { pending };
+                }
+            }
+
+            (
+                Phase::AgreeingRunOuts {
+                    mut pending,
+                    mut run_outs,
+                },
+                action,
+            ) => match action {
+                Action::RunItTimes { player, times } if pending.contains(&player) => {
+                    pending.retain(|i| *i != player);
+                    run_outs = run_outs.min(times.max(1));
+                    if pending.is_empty() {
+                        self.run_out_boards(&mut events, run_outs);
+                    } else {
+                        self.phase = Phase::AgreeingRunOuts { pending, run_outs };
+                    }
+                }
+                // A player left, so the board is only run once
+                Action::Disconnect { player } if pending.contains(&player) => {
+                    self.run_out_boards(&mut events, 1)
+                }
+                Action::Timeout => self.run_out_boards(&mut events, 1),
+                _ => self.phase = Phase::AgreeingRunOuts { pending, run_outs },
+            },
+
+            (
+                Phase::Drawing {
+                    mut pending,
+                    mut draws,
+                },
+                action,
+            ) => {
+                match action {
+                    Action::Choice {
+                        player,
+                        choice: PlayerChoice::Draw(cards),
+                    } if pending.contains(&player) => {
+                        let seat = &self.seats[player];
+                        let is_valid = cards.iter().all(|card| seat.cards.contains(card))
+                            && cards.iter().map(|card| card.value).all_unique();
+                        if is_valid {
+                            draws.push((player, cards));
+                            pending.retain(|i| *i != player);
+                        }
+                    }
+                    // Wait no longer
+                    Action::Disconnect { player } => pending.retain(|i| *i != player),
+                    Action::Timeout => pending.clear(),
+                    _ => { /* Ignore all other actions */ }
+                }
+                if pending.is_empty() {
+                    self.finish_draw(&mut events, draws);
+                } else {
+                    self.phase = Phase::Drawing { pending, draws };
+                }
+            }
+
+            (Phase::RabbitHunting { is_revealed }, Action::RabbitHunt { player }) => {
+                let is_revealed = is_revealed || self.rabbit_hunt(&mut events, player);
+                self.phase = Phase::RabbitHunting { is_revealed };
+            }
+            (Phase::RabbitHunting { .. }, Action::Timeout) => {
+                self.continue_after_pause(&mut events, Step::AfterEarlyEnd)
+            }
+
+            (Phase::Pausing(step), Action::Timeout) => self.continue_after_pause(&mut events, step),
+
+            // Ignore all other actions
+            (phase, _) => self.phase = phase,
+        }
+        events
+    }
+
+    /// Lets the engine wait for the players.
+    fn wait(&mut self, events: &mut Vec<Event>, phase: Phase, duration: Duration) {
+        self.phase = phase;
+        events.push(Event::Wait(duration));
+    }
+
+    /// Informs the players about the state of the table.
+    fn broadcast(events: &mut Vec<Event>) {
+        events.push(Event::StateChanged {
+            hand_winner: None,
+            is_showdown: false,
+        });
+    }
+
+    /// Returns the coins of all seats, as logged at the start and the end of a hand.
+    fn stacks(&self) -> Vec<usize> {
+        self.seats.iter().map(Seat::coins).collect()
+    }
+
+    /// Determines if the round ends and if it ends the winner of the round.
+    ///
+    /// Return values:
+    /// * None => Game not finished
+    /// * Some(None) => Game finished, no winner
+    /// * Some(Some(w)) => Game finished, w won
+    fn determine_end_and_game_winner(&self) -> Option<Option<usize>> {
+        // Game finished with no winner unless someone is still playing
+        let mut game_finished = Some(None);
+
+        for (i, seat) in self.seats.iter().enumerate() {
+            if !seat.is_out && seat.is_connected {
+                match game_finished {
+                    // If no other winner yet, set winner
+                    // None case should never happen because game_finished = None is never set
+                    None | Some(None) => game_finished = Some(Some(i)),
+                    // Someone else is also still in
+                    Some(Some(_)) => return None,
+                }
+            }
+        }
+        game_finished
+    }
+
+    /// Starts the next round, or ends the game if no more than one player is left.
+    /// Before the first round of a hand, the player on the button chooses the variant in dealer's choice.
+    fn start_round(&mut self, events: &mut Vec<Event>) {
+        if let Some(winner) = self.determine_end_and_game_winner() {
+            events.push(Event::GameEnd(winner));
+            return;
+        }
+        self.get_qualified_players();
+        if self.round_number != 0 {
+            self.finish_foreplay(events);
+            return;
+        }
+        events.push(Event::Checkpoint(self.state()));
+        self.hand_number += 1;
+        if self.settings.rotation == Rotation::DealersChoice && self.seats[self.dealer].is_connected
+        {
+            events.push(Event::AwaitingVariant {
+                player: self.dealer,
+                choices: self.settings.rotation.choices(),
+            });
+            self.wait(events, Phase::ChoosingVariant, VARIANT_TIMEOUT);
+            return;
+        }
+        self.start_hand(events, None);
+    }
+
+    /// Inits the first round after starting the server or showdown.
+    /// Changes the variant if the table rotates variants, sets all player cards and empties open middle cards.
+    fn start_hand(&mut self, events: &mut Vec<Event>, chosen_variant: Option<GameVariant>) {
+        let rotation = self.settings.rotation;
+        let next = match rotation {
+            Rotation::Fixed => None,
+            Rotation::Horse { .. } => rotation
+                .scheduled_change(self.settings.variant, self.hands_with_variant)
+                .filter(|(_, hands)| *hands == 0)
+                .map(|(variant, _)| variant),
+            Rotation::DealersChoice => chosen_variant,
+        };
+        if let Some(variant) = next {
+            self.settings.variant = variant;
+            self.settings.betting = rotation.betting_structure(variant);
+            self.hands_with_variant = 0;
+        }
+
+        self.card_stack.clear();
+        self.discarded_cards.clear();
+        self.open_middle_cards.clear();
+        self.extra_boards.clear();
+        self.straddles = 0;
+        if self.settings.is_shuffle_audited {
+            self.start_audited_shuffle(events);
+            return;
+        }
+        let seed = hand_seed(self.seed, self.hand_number);
+        self.rng = StdRng::seed_from_u64(seed);
+        self.card_stack = self.settings.variant.deck();
+        self.card_stack.shuffle(&mut self.rng);
+        self.deal_hand(events);
+    }
+
+    /// Commits to the order of the deck and lets the players add entropy to the shuffle.
+    fn start_audited_shuffle(&mut self, events: &mut Vec<Event>) {
+        let server_seed = match (self.revealed_seeds.get(&self.hand_number), &self.secret) {
+            (Some(server_seed), _) => *server_seed,
+            (None, Some(secret)) => shuffle::server_seed(secret, self.hand_number),
+            (None, None) => panic!("Auditable shuffles require a secret, see with_secret"),
+        };
+        self.card_stack = shuffle::server_order(self.settings.variant.deck(), &server_seed);
+        let commitment = shuffle::commitment(&self.card_stack, &server_seed);
+        self.server_seed = Some(server_seed);
+        self.client_seeds = vec![None; self.seats.len()];
+        events.push(Event::ShuffleCommitted {
+            hand_number: self.hand_number,
+            commitment,
+        });
+        let pending: Vec<usize> = self
+            .players_in_round
+            .iter()
+            .copied()
+            .filter(|i| self.seats[*i].is_connected)
+            .collect();
+        if pending.is_empty() {
+            self.finish_audited_shuffle(events);
+        } else {
+            self.wait(
+                events,
+                Phase::CollectingEntropy { pending },
+                ENTROPY_TIMEOUT,
+            );
+        }
+    }
+
+    /// Shuffles the committed deck with the entropy of the players and deals the hand.
+    fn finish_audited_shuffle(&mut self, events: &mut Vec<Event>) {
+        let server_seed = self.server_seed.expect("The deck was committed to");
+        let mixed_seed = shuffle::mix(&server_seed, &self.client_seeds);
+        let deck = std::mem::take(&mut self.card_stack);
+        self.card_stack = shuffle::final_order(deck, &mixed_seed);
+        // Everything else random in the hand follows from the revealed seeds as well
+        self.rng = StdRng::from_seed(mixed_seed);
+        self.deal_hand(events);
+    }
+
+    /// Reveals the seeds of the auditable shuffle once the hand ended.
+    fn reveal_shuffle(&mut self, events: &mut Vec<Event>) {
+        if let Some(server_seed) = self.server_seed.take() {
+            let server_seed = shuffle::to_hex(&server_seed);
+            let client_seeds = std::mem::take(&mut self.client_seeds);
+            events.push(Event::Logged(HandEvent::ShuffleRevealed {
+                hand_number: self.hand_number,
+                server_seed: server_seed.clone(),
+                client_seeds: client_seeds.clone(),
+            }));
+            events.push(Event::ShuffleRevealed {
+                hand_number: self.hand_number,
+                server_seed,
+                client_seeds,
+            });
+        }
+    }
+
+    /// Deals the hole cards from the shuffled deck and lets the players post their forced bets.
+    fn deal_hand(&mut self, events: &mut Vec<Event>) {
+        // Audited shuffles are seeded by the revealed seeds instead
+        let seed =
+            (!self.settings.is_shuffle_audited).then(|| hand_seed(self.seed, self.hand_number));
+        events.push(Event::Logged(HandEvent::HandStarted {
+            hand_number: self.hand_number,
+            seed,
+            variant: self.settings.variant,
+            betting: self.settings.betting,
+            dealer: self.dealer,
+            small_blind: self.small_blind,
+            players: self.players_in_round.clone(),
+            stacks: self.stacks(),
+        }));
+        self.hand_players = self.players_in_round.clone();
+        // There are no more than 6 players, cards always suffice
+        for i in &self.players_in_round {
+            let seat = &mut self.seats[*i];
+            // Assert clean for each player
+            assert_eq!(seat.cards.len(), 0);
+            assert_eq!(seat.current_betting_amount(), 0);
+            for _ in 0..self.settings.variant.hole_cards() {
+                seat.cards.push(self.card_stack.pop().unwrap());
+            }
+            if self.settings.variant.is_stud() {
+                // The last card is the door card, which is dealt face up
+                seat.up_cards.push(*seat.cards.last().unwrap());
+            }
+            events.push(Event::Logged(HandEvent::CardsDealt {
+                player: *i,
+                cards: seat.cards.clone(),
+                up_cards: seat.up_cards.clone(),
+            }));
+        }
+
+        // Only variants with a board can be played as bomb pots
+        if let Some(bomb_pot) = self.settings.bomb_pot {
+            if self.settings.variant.board_cards() > 0 {
+                if bomb_pot
+                    .interval
+                    .is_some_and(|interval| self.hand_number.is_multiple_of(interval))
+                {
+                    self.post_forced_bets(events, true);
+                    return;
+                }
+                let pending = self.players_in_round.clone();
+                if bomb_pot.is_voted && pending.iter().all(|i| self.seats[*i].is_connected) {
+                    events.push(Event::AwaitingBombPotVote {
+                        players: pending.clone(),
+                    });
+                    self.wait(
+                        events,
+                        Phase::VotingBombPot { pending },
+                        BOMB_POT_VOTE_TIMEOUT,
+                    );
+                    return;
+                }
+            }
+        }
+        self.post_forced_bets(events, false);
+    }
+
+    /// Sets the antes of a bomb pot, the bring-in in stud games or the blinds, which may be straddled.
+    fn post_forced_bets(&mut self, events: &mut Vec<Event>, is_bomb_pot: bool) {
+        self.is_bomb_pot = is_bomb_pot;
+        if let (true, Some(bomb_pot)) = (self.is_bomb_pot, self.settings.bomb_pot) {
+            if bomb_pot.is_double_board {
+                self.extra_boards = vec![vec![]];
+            }
+            // Every player antes instead of paying blinds
+            for i in self.players_in_round.clone() {
+                let ante = bomb_pot.ante;
+                self.post_forced_bet(events, i, ForcedBet::Ante, ante, ante, ante);
+            }
+            self.finish_foreplay(events);
+            return;
+        }
+
+        if self.settings.variant.is_stud() {
+            let door_cards: Vec<Card> = self
+                .players_in_round
+                .iter()
+                .map(|i| self.seats[*i].up_cards[0])
+                .collect();
+            // The player with the worst door card brings in
+            if let Some(index) = self.settings.variant.bring_in(&door_cards) {
+                let player = self.players_in_round[index];
+                let blind = self.small_blind;
+                self.post_forced_bet(events, player, ForcedBet::BringIn, blind, blind, usize::MAX);
+            }
+            self.finish_foreplay(events);
+            return;
+        }
+
+        let blind = self.small_blind;
+        // First player after dealer pays blind
+        if let Some(&player) = self.players_in_round.first() {
+            self.post_forced_bet(
+                events,
+                player,
+                ForcedBet::SmallBlind,
+                blind,
+                blind,
+                usize::MAX,
+            );
+        }
+        // Second player after dealer pays blind * 2
+        if let Some(&player) = self.players_in_round.get(1) {
+            let kind = ForcedBet::BigBlind;
+            self.post_forced_bet(events, player, kind, 2 * blind, blind, usize::MAX);
+        }
+        if self.settings.straddles == StraddleRule::Off || self.players_in_round.len() < 3 {
+            self.finish_foreplay(events);
+            return;
+        }
+        // The player after the big blind may straddle first
+        self.offer_straddle(events, 2, 4 * self.small_blind, false);
+    }
+
+    /// Posts the forced bet of the player and logs what he actually posted, which is less if he is all in.
+    fn post_forced_bet(
+        &mut self,
+        events: &mut Vec<Event>,
+        player: usize,
+        kind: ForcedBet,
+        amount: usize,
+        minimal_amount: usize,
+        maximal_amount: usize,
+    ) {
+        let seat = &mut self.seats[player];
+        Self::try_set_player_bet(
+            self.small_blind,
+            seat,
+            amount,
+            minimal_amount,
+            maximal_amount,
+        );
+        events.push(Event::Logged(HandEvent::BlindPosted {
+            player,
+            kind,
+            amount: seat.current_betting_amount(),
+        }));
+    }
+
+    /// Asks the player at the position whether he wants to straddle.
+    /// Players who can not afford the straddle are not asked.
+    fn offer_straddle(
+        &mut self,
+        events: &mut Vec<Event>,
+        position: usize,
+        amount: usize,
+        is_button: bool,
+    ) {
+        let player = self.players_in_round[position];
+        let seat = &self.seats[player];
+        if !seat.is_connected || seat.coins() < amount {
+            self.continue_straddles(events, position, amount, is_button, false);
+            return;
+        }
+        events.push(Event::AwaitingStraddle { player, amount });
+        let phase = Phase::Straddling {
+            position,
+            amount,
+            is_button,
+        };
+        self.wait(events, phase, STRADDLE_TIMEOUT);
+    }
+
+    /// Continues after the player at the position straddled or not.
+    /// The player after the big blind may be re-straddled by the following players, if the table allows it.
+    /// Otherwise the player on the button may straddle. The last straddler acts last before the flop.
+    fn continue_straddles(
+        &mut self,
+        events: &mut Vec<Event>,
+        position: usize,
+        amount: usize,
+        is_button: bool,
+        is_straddling: bool,
+    ) {
+        let len = self.players_in_round.len();
+        let button = len - 1;
+        if is_straddling {
+            let player = self.players_in_round[position];
+            self.post_forced_bet(events, player, ForcedBet::Straddle, amount, amount, amount);
+            self.straddles += 1;
+            // The player on the button is the last one who may re-straddle
+            if !is_button
+                && self.settings.straddles == StraddleRule::Restraddle
+                && position < button
+            {
+                self.offer_straddle(events, position + 1, 2 * amount, false);
+                return;
+            }
+            self.players_in_round.rotate_left((position + 1) % len);
+        } else if self.straddles > 0 {
+            // The previous player was the last straddler
+            self.players_in_round.rotate_left(position % len);
+        } else if !is_button && button > 2 {
+            self.offer_straddle(events, button, amount, true);
+            return;
+        }
+        self.finish_foreplay(events);
+    }
+
+    /// The part before the actual playing.
+    fn finish_foreplay(&mut self, events: &mut Vec<Event>) {
+        if self.settings.variant.is_stud() {
+            self.order_players_by_up_cards();
+        }
+        Self::broadcast(events);
+        self.start_main_play(events);
+    }
+
+    /// Orders the players in the round by the face up cards, as in stud games.
+    /// On the first street, the player after the bring-in acts first, so the bring-in acts last.
+    /// On later streets, the player with the best face up cards acts first.
+    fn order_players_by_up_cards(&mut self) {
+        let variant = self.settings.variant;
+        let seats = &self.seats;
+        if self
+            .players_in_round
+            .iter()
+            .any(|i| seats[*i].up_cards.is_empty())
+        {
+            return;
+        }
+        let first = if self.round_number < 2 {
+            let door_cards: Vec<Card> = self
+                .players_in_round
+                .iter()
+                .map(|i| seats[*i].up_cards[0])
+                .collect();
+            variant.bring_in(&door_cards).map(|index| index + 1)
+        } else {
+            // Ties go to the first player after the dealer
+            self.players_in_round
+                .iter()
+                .enumerate()
+                .max_by_key(|(index, i)| {
+                    (
+                        variant.value_of_up_cards(&seats[**i].up_cards),
+                        Reverse(*index),
+                    )
+                })
+                .map(|(index, _)| index)
+        };
+        if let Some(first) = first {
+            let len = self.players_in_round.len();
+            self.players_in_round.rotate_left(first % len);
+        }
+    }
+
+    /// The actual part of playing (raise, check, etc.).
+    fn start_main_play(&mut self, events: &mut Vec<Event>) {
+        // Assert that no player in the round has folded
+        // players_in_round only contains players which have not folded before playing
+        assert!(self
+            .players_in_round
+            .iter()
+            .all(|i| !self.seats[*i].has_folded));
+        if self.players_in_round.len() <= 1 {
+            // Not enough players, skip
+            self.afterplay(events);
+        } else if self.is_all_in_run_out() {
+            // No one can bet anymore, skip
+            self.afterplay(events);
+        } else if self.is_bomb_pot && self.round_number < 2 {
+            // The betting starts on the flop
+            self.afterplay(events);
+        } else if self.round_number.is_multiple_of(2) {
+            // The big blind counts as the first bet before the flop, but the bring-in does not
+            let is_blind_round = self.round_number == 0 && !self.settings.variant.is_stud();
+            let raises = if is_blind_round {
+                1 + self.straddles
+            } else {
+                0
+            };
+            self.prompt_bet(events, 0, raises);
+        } else {
+            self.prompt_adjustment(events, 0);
+        }
+    }
+
+    /// Lets the player at the index bet.
+    fn prompt_bet(&mut self, events: &mut Vec<Event>, index: usize, raises: usize) {
+        if index >= self.players_in_round.len() {
+            self.afterplay(events);
+            return;
+        }
+        let player = self.players_in_round[index];
+        if !self.seats[player].is_connected {
+            // Always go all in if not connected
+            self.resolve_bet(
+                events,
+                index,
+                raises,
+                Some(PlayerChoice::RaiseTo(usize::MAX)),
+            );
+            return;
+        }
+        let situation = self.get_betting_situation(player, raises);
+        let range = self.settings.betting.legal_range(&situation);
+        events.push(Event::AwaitingBet { player, range });
+        self.wait(events, Phase::Betting { index, raises }, BET_TIMEOUT);
+    }
+
+    /// Applies the choice of the player at the index. No choice or any choice other than raise or pass is a fold.
+    fn resolve_bet(
+        &mut self,
+        events: &mut Vec<Event>,
+        index: usize,
+        raises: usize,
+        choice: Option<PlayerChoice>,
+    ) {
+        let player = self.players_in_round[index];
+        let highest_bet = self.get_highest_bet_in_round();
+        let situation = self.get_betting_situation(player, raises);
+        let range = self.settings.betting.legal_range(&situation);
+        let seat = &mut self.seats[player];
+        let previous_bet = seat.current_betting_amount();
+        match choice {
+            Some(PlayerChoice::RaiseTo(amount)) => {
+                let amount = self.settings.betting.validate(&situation, amount);
+                Self::try_set_player_bet(
+                    self.small_blind,
+                    seat,
+                    amount,
+                    range.minimal,
+                    range.maximal,
+                );
+            }
+            Some(PlayerChoice::Pass) => {
+                let amount = seat.current_betting_amount();
+                Self::try_set_player_bet(
+                    self.small_blind,
+                    seat,
+                    amount,
+                    range.minimal,
+                    range.maximal,
+                );
+            }
+            _ => {
+                // All action which is not raise is considered fold!
+                // So that players who do not play are automatically excluded from the rounds
+                seat.has_folded = true;
+            }
+        };
+        self.log_action(events, player, previous_bet, highest_bet, raises);
+        let raises = if self.seats[player].current_betting_amount() > highest_bet {
+            raises + 1
+        } else {
+            raises
+        };
+        Self::broadcast(events);
+        self.prompt_bet(events, index + 1, raises);
+    }
+
+    /// Lets the player at the index adjust his bet, if it is lower than the highest bet.
+    fn prompt_adjustment(&mut self, events: &mut Vec<Event>, index: usize) {
+        if index >= self.players_in_round.len() {
+            self.afterplay(events);
+            return;
+        }
+        let player = self.players_in_round[index];
+        let highest_bet = self.get_highest_bet_in_round();
+        let seat = &self.seats[player];
+        // Only necessary if bets are unequally high
+        if seat.current_betting_amount() == highest_bet {
+            Self::broadcast(events);
+            self.prompt_adjustment(events, index + 1);
+        } else if !seat.is_connected {
+            // Always go all in if not connected
+            self.resolve_adjustment(events, index, Some(PlayerChoice::RaiseTo(usize::MAX)));
+        } else {
+            let range = BetRange {
+                minimal: highest_bet,
+                maximal: highest_bet,
+            };
+            events.push(Event::AwaitingBet { player, range });
+            self.wait(events, Phase::Adjusting { index }, BET_TIMEOUT);
+        }
+    }
+
+    /// Applies the choice of the player at the index: raise and pass call the highest bet, anything else folds.
+    fn resolve_adjustment(
+        &mut self,
+        events: &mut Vec<Event>,
+        index: usize,
+        choice: Option<PlayerChoice>,
+    ) {
+        let highest_bet = self.get_highest_bet_in_round();
+        let player = self.players_in_round[index];
+        let seat = &mut self.seats[player];
+        let previous_bet = seat.current_betting_amount();
+        match choice {
+            Some(PlayerChoice::RaiseTo(_) | PlayerChoice::Pass) => {
+                Self::try_set_player_bet(
+                    self.small_blind,
+                    seat,
+                    highest_bet,
+                    highest_bet,
+                    highest_bet,
+                );
+            }
+            _ => {
+                // All action which is not raise is considered fold!
+                // So that players who do not play are automatically excluded from the rounds
+                seat.has_folded = true;
+            }
+        };
+        self.log_action(events, player, previous_bet, highest_bet, 0);
+        Self::broadcast(events);
+        self.prompt_adjustment(events, index + 1);
+    }
+
+    /// Logs what the player did, given his bet before he acted, the highest bet he faced
+    /// and the amount of raises before him on this street.
+    fn log_action(
+        &self,
+        events: &mut Vec<Event>,
+        player: usize,
+        previous_bet: usize,
+        highest_bet: usize,
+        raises: usize,
+    ) {
+        let seat = &self.seats[player];
+        let total = seat.current_betting_amount();
+        let action = if seat.has_folded {
+            Move::Fold
+        } else if total == previous_bet {
+            Move::Check
+        } else if total <= highest_bet {
+            Move::Call
+        } else if raises == 0 {
+            Move::Bet
+        } else {
+            Move::Raise
+        };
+        events.push(Event::Logged(HandEvent::ActionTaken {
+            player,
+            action,
+            amount: total - previous_bet,
+            total,
+            is_all_in: total > previous_bet && seat.coins() == 0,
+        }));
+    }
+
+    /// Collects what the betting structure needs to know about the player who has to act.
+    fn get_betting_situation(&self, player_id: usize, raises: usize) -> BettingSituation {
+        let seat = &self.seats[player_id];
+        BettingSituation {
+            current_bet: seat.current_betting_amount(),
+            highest_bet: self.get_highest_bet_in_round(),
+            pot: self.seats.iter().map(Seat::current_betting_amount).sum(),
+            stack: seat.coins() + seat.current_betting_amount(),
+            big_blind: 2 * self.small_blind,
+            street: self.round_number / 2,
+            raises,
+        }
+    }
+
+    /// Tries to set a players bet to a specified amount.
+    /// Bet may not exceed players coin amount.
+    /// Bet should be higher or equal than highest bet of others (minimal_amount).
+    /// Bet may not exceed the betting limit (maximal_amount).
+    /// Bet should be multiple of small blind.
+    fn try_set_player_bet(
+        current_blind: usize,
+        seat: &mut Seat,
+        mut amount: usize,
+        minimal_amount: usize,
+        maximal_amount: usize,
+    ) {
+        // Must raise to at least amount of all previous players
+        // Or all in
+
+        // Amount must be higher or equal than previous amount
+        amount = std::cmp::max(amount, seat.current_betting_amount());
+
+        // Amount must be higher or equal than all other players' bets
+        amount = std::cmp::max(amount, minimal_amount);
+
+        // Amount must be multiple of small blind
+        // Round down if too big
+        let remainder = amount % current_blind;
+        if amount > current_blind && usize::MAX - current_blind < amount {
+            amount -= remainder;
+        } else if remainder != 0 {
+            amount += current_blind - remainder;
+        }
+
+        // Amount may not be higher than the limit
+        amount = std::cmp::min(amount, std::cmp::max(maximal_amount, minimal_amount));
+
+        // Amount may not be higher than current owned amount
+        amount = std::cmp::min(amount, seat.coins() + seat.current_betting_amount());
+
+        // Make player bet
+        seat.deduct_from_money_to_bet(amount);
+    }
+
+    /// Represents the game path after every round played.
+    fn afterplay(&mut self, events: &mut Vec<Event>) {
+        // If last_player is None, then there are more than two players left
+        // If last_player is Some(i), the i is the last player left
+        // If last_player is Some(None), then there are no players left
+        let mut last_player = Some(None);
+        for i in &self.players_in_round {
+            if !self.seats[*i].has_folded {
+                match last_player {
+                    None => break,
+                    Some(t) => match t {
+                        Some(_) => {
+                            last_player = None;
+                            break;
+                        }
+                        None => last_player = Some(Some(*i)),
+                    },
+                }
+            }
+        }
+
+        if let Some(maybe_player) = last_player {
+            self.early_end(events, maybe_player);
+            events.push(Event::StateChanged {
+                hand_winner: maybe_player,
+                is_showdown: true,
+            });
+            self.reveal_shuffle(events);
+            self.unfold_and_out_players();
+            self.round_number = 0;
+            self.move_dealer_and_increase_blind();
+            for seat in &mut self.seats {
+                seat.cards.clear();
+                seat.up_cards.clear();
+                seat.equity = None;
+            }
+            // Let everyone look at stats
+            let phase = match self.settings.rabbit_hunt_interval {
+                Some(_) => Phase::RabbitHunting { is_revealed: false },
+                None => Phase::Pausing(Step::AfterEarlyEnd),
+            };
+            self.wait(events, phase, EARLY_END_DELAY);
+            return;
+        }
+
+        // Enough players remain to continue normally
+        let street = self.round_number / 2;
+        if self.round_number % 2 == 1 && self.settings.variant.discard_street() == Some(street) {
+            self.start_discard(events);
+        } else {
+            self.reveal_run_out(events);
+        }
+    }
+
+    /// Continues the round after the players looked at the table.
+    fn continue_after_pause(&mut self, events: &mut Vec<Event>, step: Step) {
+        match step {
+            Step::AfterEarlyEnd => {
+                Self::broadcast(events);
+                self.start_round(events);
+            }
+            Step::DealStreet => self.deal_street(events),
+            Step::Payout(board_winners) => self.pay_showdown(events, board_winners),
+            Step::NextRound => self.next_round(events),
+        }
+    }
+
+    /// Reveals the hands as soon as the players are all in and lets them agree on the amount of run-outs.
+    /// Hands are only revealed once they are final.
+    fn reveal_run_out(&mut self, events: &mut Vec<Event>) {
+        let is_draw_pending = self.round_number / 2 < self.settings.variant.draws();
+        self.is_running_out =
+            self.is_all_in_run_out() && !self.is_discard_pending() && !is_draw_pending;
+        if !self.is_running_out || self.seats.iter().any(|seat| seat.equity.is_some()) {
+            self.deal_street(events);
+            return;
+        }
+        let board_cards = self.settings.variant.board_cards();
+        // The boards of a double board bomb pot are not run out again
+        let is_single_board = self.extra_boards.is_empty();
+        if self.open_middle_cards.len() >= board_cards
+            || self.settings.max_run_outs <= 1
+            || !is_single_board
+        {
+            self.show_equities(events, Step::DealStreet);
+            return;
+        }
+        let pending: Vec<usize> = self
+            .players_in_round
+            .iter()
+            .copied()
+            .filter(|i| !self.seats[*i].has_folded && self.seats[*i].is_connected)
+            .collect();
+        if pending.len() < 2 {
+            self.run_out_boards(events, 1);
+            return;
+        }
+        let max_run_outs = self.settings.max_run_outs;
+        events.push(Event::AwaitingRunOuts {
+            players: pending.clone(),
+            max_run_outs,
+        });
+        let phase = Phase::AgreeingRunOuts {
+            pending,
+            run_outs: max_run_outs,
+        };
+        self.wait(events, phase, RUN_IT_TIMEOUT);
+    }
+
+    /// Deals the boards the players agreed on and shows the equities.
+    fn run_out_boards(&mut self, events: &mut Vec<Event>, run_outs: usize) {
+        self.extra_boards = vec![self.open_middle_cards.clone(); run_outs - 1];
+        self.show_equities(events, Step::DealStreet);
+    }
+
+    /// Calculates the equities of the players and lets them look at them, then continues with the step.
+    fn show_equities(&mut self, events: &mut Vec<Event>, step: Step) {
+        self.update_all_in_equities();
+        Self::broadcast(events);
+        self.wait(events, Phase::Pausing(step), ALL_IN_STREET_DELAY);
+    }
+
+    /// Deals the cards at the end of the round, lets the players draw or ends the hand with the showdown.
+    fn deal_street(&mut self, events: &mut Vec<Event>) {
+        let street = self.round_number / 2;
+        let last_round = 2 * self.settings.variant.streets() - 1;
+        match self.round_number {
+            // Everything after the last round symbolises game end
+            r if r >= last_round => self.start_showdown(events),
+            r if r % 2 == 1 && street < self.settings.variant.draws() => self.start_draw(events),
+            r if r % 2 == 1 => {
+                self.deal_next_street(events);
+                self.show_street_equities(events);
+            }
+            _ => self.show_street_equities(events),
+        }
+    }
+
+    /// Shows the equities after every street of an all-in run-out, then continues with the next round.
+    fn show_street_equities(&mut self, events: &mut Vec<Event>) {
+        let last_round = 2 * self.settings.variant.streets() - 1;
+        if self.is_running_out && self.round_number % 2 == 1 && self.round_number < last_round {
+            self.show_equities(events, Step::NextRound);
+        } else {
+            self.next_round(events);
+        }
+    }
+
+    /// Determines the next round and starts it.
+    fn next_round(&mut self, events: &mut Vec<Event>) {
+        self.determine_next_round_and_move_dealer();
+        Self::broadcast(events);
+        self.start_round(events);
+    }
+
+    /// Checks whether at least two players remain, but no more than one of them can still bet.
+    /// In that case the remaining cards are dealt without any further betting.
+    fn is_all_in_run_out(&self) -> bool {
+        let highest_bet = self.get_highest_bet_in_round();
+        let remaining: Vec<&Seat> = self
+            .players_in_round
+            .iter()
+            .map(|i| &self.seats[*i])
+            .filter(|seat| !seat.has_folded)
+            .collect();
+        let can_bet: Vec<&&Seat> = remaining.iter().filter(|seat| seat.coins() > 0).collect();
+
+        remaining.len() >= 2
+            && can_bet.len() <= 1
+            && can_bet
+                .iter()
+                .all(|seat| seat.current_betting_amount() >= highest_bet)
+    }
+
+    /// Checks whether any remaining player still has to discard a hole card.
+    fn is_discard_pending(&self) -> bool {
+        let hole_cards = self.settings.variant.showdown_hole_cards();
+        self.players_in_round
+            .iter()
+            .map(|i| &self.seats[*i])
+            .any(|seat| !seat.has_folded && seat.cards.len() > hole_cards)
+    }
+
+    /// Lets all remaining players discard one hole card at the same time.
+    /// The discarded cards are never shown to the opponents.
+    fn start_discard(&mut self, events: &mut Vec<Event>) {
+        let hole_cards = self.settings.variant.showdown_hole_cards();
+        // Players who are not connected do not need to be waited for
+        let pending: Vec<usize> = self
+            .players_in_round
+            .iter()
+            .copied()
+            .filter(|i| {
+                let seat = &self.seats[*i];
+                !seat.has_folded && seat.cards.len() > hole_cards && seat.is_connected
+            })
+            .collect();
+        if pending.is_empty() {
+            self.finish_discard(events);
+            return;
+        }
+        events.push(Event::AwaitingDiscard {
+            players: pending.clone(),
+        });
+        self.wait(events, Phase::Discarding { pending }, DISCARD_TIMEOUT);
+    }
+
+    /// Players who did not discard a card of their hand in time discard their last card.
+    fn finish_discard(&mut self, events: &mut Vec<Event>) {
+        let hole_cards = self.settings.variant.showdown_hole_cards();
+        for i in &self.players_in_round {
+            let seat = &mut self.seats[*i];
+            while !seat.has_folded && seat.cards.len() > hole_cards {
+                let card = seat.cards.pop().unwrap();
+                events.push(Event::Logged(HandEvent::CardDiscarded { player: *i, card }));
+            }
+        }
+        Self::broadcast(events);
+        self.reveal_run_out(events);
+    }
+
+    /// Lets all remaining players exchange any of their cards at the same time, as in draw games.
+    fn start_draw(&mut self, events: &mut Vec<Event>) {
+        // Players who are not connected do not need to be waited for
+        let pending: Vec<usize> = self
+            .players_in_round
+            .iter()
+            .copied()
+            .filter(|i| !self.seats[*i].has_folded && self.seats[*i].is_connected)
+            .collect();
+        if pending.is_empty() {
+            self.finish_draw(events, vec![]);
+            return;
+        }
+        events.push(Event::AwaitingDraw {
+            players: pending.clone(),
+        });
+        let phase = Phase::Drawing {
+            pending,
+            draws: vec![],
+        };
+        self.wait(events, phase, DRAW_TIMEOUT);
+    }
+
+    /// Replaces the cards the players chose. Players who did not choose valid cards in time keep their hand.
+    fn finish_draw(&mut self, events: &mut Vec<Event>, draws: Vec<(usize, Vec<Card>)>) {
+        // Replace the cards in the order of the players
+        for i in self.players_in_round.clone() {
+            if self.seats[i].has_folded {
+                continue;
+            }
+            let cards = match draws.iter().find(|(j, _)| *j == i) {
+                Some((_, cards)) => cards.clone(),
+                None => vec![],
+            };
+            let mut drawn = vec![];
+            for card in &cards {
+                if self.card_stack.is_empty() {
+                    // The cards the current player throws away are not reshuffled
+                    self.card_stack.append(&mut self.discarded_cards);
+                    self.card_stack.shuffle(&mut self.rng);
+                }
+                let seat = &mut self.seats[i];
+                let index = seat.cards.iter().position(|c| c == card).unwrap();
+                seat.cards[index] = self.card_stack.pop().unwrap();
+                drawn.push(seat.cards[index]);
+            }
+            self.discarded_cards.extend(cards.iter().copied());
+            events.push(Event::Logged(HandEvent::CardsDrawn {
+                player: i,
+                discarded: cards,
+                drawn,
+            }));
+        }
+        Self::broadcast(events);
+        self.show_street_equities(events);
+    }
+
+    /// Deals the cards of the next street: 3 cards and then 1 card to every board,
+    /// or 1 card to every remaining player in stud games, which is face down on the last street.
+    fn deal_next_street(&mut self, events: &mut Vec<Event>) {
+        let street = self.round_number / 2 + 1;
+        if !self.settings.variant.is_stud() {
+            self.deal_to_boards(events, if street == 1 { 3 } else { 1 });
+            return;
+        }
+        let is_face_up = street + 1 < self.settings.variant.streets();
+        for i in &self.players_in_round {
+            let seat = &mut self.seats[*i];
+            if !seat.has_folded {
+                let card = self.card_stack.pop().unwrap();
+                seat.cards.push(card);
+                if is_face_up {
+                    seat.up_cards.push(card);
+                }
+                events.push(Event::Logged(HandEvent::CardsDealt {
+                    player: *i,
+                    cards: vec![card],
+                    up_cards: if is_face_up { vec![card] } else { vec![] },
+                }));
+            }
+        }
+    }
+
+    /// Deals the specified amount of cards to every board.
+    fn deal_to_boards(&mut self, events: &mut Vec<Event>, amount: usize) {
+        let cards: Vec<Card> = (0..amount)
+            .map(|_| self.card_stack.pop().unwrap())
+            .collect();
+        self.open_middle_cards.extend(&cards);
+        events.push(Event::Logged(HandEvent::BoardDealt { board: 0, cards }));
+        for (index, board) in self.extra_boards.iter_mut().enumerate() {
+            let cards: Vec<Card> = (0..amount)
+                .map(|_| self.card_stack.pop().unwrap())
+                .collect();
+            board.extend(&cards);
+            events.push(Event::Logged(HandEvent::BoardDealt {
+                board: index + 1,
+                cards,
+            }));
+        }
+    }
+
+    /// Returns all boards, starting with the open middle cards.
+    fn boards(&self) -> Vec<Vec<Card>> {
+        let mut boards = vec![self.open_middle_cards.clone()];
+        boards.extend(self.extra_boards.iter().cloned());
+        boards
+    }
+
+    /// Calculates the equity of every remaining player on the current boards, which also reveals their cards.
+    /// With several boards, the equity is the average over all boards, as each board pays an equal share of the pot.
+    fn update_all_in_equities(&mut self) {
+        let remaining: Vec<usize> = self
+            .players_in_round
+            .iter()
+            .copied()
+            .filter(|i| !self.seats[*i].has_folded)
+            .collect();
+        let hands: Vec<Vec<Card>> = remaining
+            .iter()
+            .map(|i| self.seats[*i].cards.clone())
+            .collect();
+        let boards = self.boards();
+        // The face up cards of folded players can not show up anymore
+        let folded_up_cards: Vec<Card> = self
+            .seats
+            .iter()
+            .filter(|seat| seat.has_folded)
+            .flat_map(|seat| seat.up_cards.iter().copied())
+            .collect();
+        let mut shares = vec![0.0; remaining.len()];
+        for board in &boards {
+            // Cards of the other boards can not show up on this board anymore
+            let dead: Vec<Card> = boards
+                .iter()
+                .flatten()
+                .filter(|card| !board.contains(card))
+                .chain(&folded_up_cards)
+                .copied()
+                .collect();
+            match equity::calculate_variant_equity(
+                self.settings.variant,
+                &hands,
+                board,
+                &dead,
+                DEFAULT_ITERATIONS,
+                self.rng.gen(),
+            ) {
+                Ok(result) => {
+                    for (share, e) in shares.iter_mut().zip(result.equities) {
+                        *share += e.share / boards.len() as f64;
+                    }
+                }
+                // The equities are not shown if they can not be calculated
+                Err(_) => return,
+            }
+        }
+        for (i, share) in remaining.iter().zip(shares) {
+            self.seats[*i].equity = Some(share);
+        }
+    }
+
+    /// Represents the game path if there are not enough players during a round.
+    fn early_end(&mut self, events: &mut Vec<Event>, maybe_last: Option<usize>) {
+        if let Some(winner) = maybe_last {
+            let mut pot = 0;
+            let max = self.seats[winner].current_betting_amount();
+            let bets: Vec<usize> = self
+                .seats
+                .iter_mut()
+                .map(|seat| seat.take_betting_amount(max))
+                .collect();
+            for bet in &bets {
+                pot += bet;
+            }
+            self.seats[winner].add_coins(pot);
+            let mut payouts = vec![0; bets.len()];
+            payouts[winner] = pot;
+            Self::log_payouts(events, &bets, &payouts);
+        } else {
+            for seat in &mut self.seats {
+                // Return all investments
+                seat.take_betting_amount(0);
+            }
+        }
+        events.push(Event::Logged(HandEvent::HandEnded {
+            hand_number: self.hand_number,
+            stacks: self.stacks(),
+        }));
+    }
+
+    /// Logs the part of the highest bet nobody called as returned and everything else paid as won.
+    fn log_payouts(events: &mut Vec<Event>, bets: &[usize], payouts: &[usize]) {
+        let mut returned = vec![0; bets.len()];
+        if let Some((player, bet)) = bets.iter().enumerate().max_by_key(|(_, bet)| **bet) {
+            let called = (0..bets.len())
+                .filter(|i| *i != player)
+                .map(|i| bets[i])
+                .max()
+                .unwrap_or(0);
+            returned[player] = bet.saturating_sub(called).min(payouts[player]);
+        }
+        for (player, (payout, returned)) in payouts.iter().zip(returned).enumerate() {
+            if returned > 0 {
+                events.push(Event::Logged(HandEvent::BetReturned {
+                    player,
+                    amount: returned,
+                }));
+            }
+            if payout > &returned {
+                events.push(Event::Logged(HandEvent::PotAwarded {
+                    player,
+                    amount: payout - returned,
+                }));
+            }
+        }
+    }
+
+    /// Reveals the cards which would have been dealt after the hand ended early,
+    /// if the player was dealt into the hand and may hunt.
+    /// The first request of the hand counts against the requesting player's limit.
+    /// Returns whether the cards were revealed.
+    fn rabbit_hunt(&mut self, events: &mut Vec<Event>, player: usize) -> bool {
+        let interval = match self.settings.rabbit_hunt_interval {
+            Some(interval) => interval,
+            None => return false,
+        };
+        // The remaining cards are dealt from the top of the stack
+        let missing = self
+            .settings
+            .variant
+            .board_cards()
+            .saturating_sub(self.open_middle_cards.len());
+        if missing == 0 || !self.hand_players.contains(&player) {
+            return false;
+        }
+        let hunter = &mut self.seats[player];
+        if let Some(last) = hunter.last_rabbit_hunt {
+            if self.hand_number - last < interval {
+                return false;
+            }
+        }
+        hunter.last_rabbit_hunt = Some(self.hand_number);
+
+        let cards: Vec<Card> = self
+            .card_stack
+            .iter()
+            .rev()
+            .take(missing)
+            .copied()
+            .collect();
+        events.push(Event::RabbitHuntResult {
+            requested_by: player,
+            cards,
+        });
+        true
+    }
+
+    /// Unfolds and outs all players.
+    fn unfold_and_out_players(&mut self) {
+        for seat in &mut self.seats {
+            if seat.coins() == 0 || !seat.is_connected {
+                seat.is_out = true;
+            }
+            seat.has_folded = false;
+        }
+    }
+
+    /// Determines the winners of every board and shows them before the pot is paid.
+    fn start_showdown(&mut self, events: &mut Vec<Event>) {
+        let boards = self.boards();
+        let mut board_winners = vec![];
+        for board in &boards {
+            board_winners.push(self.determine_board_winners(board));
+        }
+        for i in &self.players_in_round {
+            let seat = &self.seats[*i];
+            if !seat.has_folded && (seat.end_of_round_values.is_some() || seat.equity.is_some()) {
+                events.push(Event::Logged(HandEvent::HandShown {
+                    player: *i,
+                    cards: seat.cards.clone(),
+                }));
+            }
+        }
+        let winner = board_winners[0].high.first().copied();
+        self.showdown_winners = board_winners.clone();
+        events.push(Event::StateChanged {
+            hand_winner: winner,
+            is_showdown: true,
+        });
+        // Wait for everyone to see the cards before moving money
+        self.wait(
+            events,
+            Phase::Pausing(Step::Payout(board_winners)),
+            SHOWDOWN_DELAY,
+        );
+    }
+
+    /// Pays the winners of every board. Every board pays an equal share of the pot, the first board also pays the remainder.
+    fn pay_showdown(&mut self, events: &mut Vec<Event>, board_winners: Vec<BoardWinners>) {
+        for seat in &mut self.seats {
+            seat.end_of_round_values = None;
+        }
+        self.showdown_winners.clear();
+
+        let bets: Vec<usize> = self
+            .seats
+            .iter_mut()
+            .map(|seat| seat.take_betting_amount(usize::MAX))
+            .collect();
+        let payouts = calculate_payouts(&bets, &board_winners);
+        for (seat, payout) in self.seats.iter_mut().zip(&payouts) {
+            seat.add_coins(*payout);
+        }
+        Self::log_payouts(events, &bets, &payouts);
+        events.push(Event::Logged(HandEvent::HandEnded {
+            hand_number: self.hand_number,
+            stacks: self.stacks(),
+        }));
+        Self::broadcast(events);
+        self.reveal_shuffle(events);
+
+        for seat in &mut self.seats {
+            seat.cards.clear();
+            seat.up_cards.clear();
+            seat.equity = None;
+        }
+        self.unfold_and_out_players();
+        self.show_street_equities(events);
+    }
+
+    /// Determines the winners of a single board and lets the players who could win show their cards.
+    /// Only in hi-lo variants a tied half of the pot is split, otherwise a tie returns the pot.
+    fn determine_board_winners(&mut self, board: &[Card]) -> BoardWinners {
+        let variant = self.settings.variant;
+        let mut winners = BoardWinners::default();
+        let mut best_high = 0;
+        let mut best_low = None;
+        for i in &self.players_in_round {
+            let seat = &mut self.seats[*i];
+            // Player might have folded
+            if seat.has_folded {
+                continue;
+            }
+            // If the current val is not at least as good as the previous players,
+            // the player has no incentive to show what cards he had
+            let mut can_win = false;
+            let curr_val = seat.determine_card_value(variant, board);
+            if winners.high.is_empty() || curr_val > best_high {
+                winners.high = vec![*i];
+                best_high = curr_val;
+                can_win = true;
+            } else if curr_val == best_high {
+                winners.high.push(*i);
+                can_win = true;
+            }
+            if let Some(low_val) = variant.low_value_of_hand(&seat.cards, board) {
+                if best_low < Some(low_val) {
+                    winners.low = vec![*i];
+                    best_low = Some(low_val);
+                    can_win = true;
+                } else if best_low == Some(low_val) {
+                    winners.low.push(*i);
+                    can_win = true;
+                }
+            }
+            if can_win {
+                seat.end_of_round_values = Some(curr_val);
+            }
+        }
+        if winners.high.len() > 1 && !variant.is_hi_lo() {
+            // If multiple people win, no one wins! This is the house rule of the table, every bet is returned.
+            // Hi-lo pots are split in any case, and ties for the low are common, so the rule would refund
+            // one half while the other is won. There the tied players share their half instead.
+            winners.high.clear();
+        }
+        winners
+    }
+
+    /// Retrieves the highest bet in the round.
+    fn get_highest_bet_in_round(&self) -> usize {
+        self.seats
+            .iter()
+            .map(|seat| seat.current_betting_amount())
+            .fold(0, std::cmp::max)
+    }
+
+    /// Determines the next round and moves the dealer.
+    fn determine_next_round_and_move_dealer(&mut self) {
+        self.round_number += 1;
+        // There are only two rounds per street
+        if self.round_number >= 2 * self.settings.variant.streets() {
+            // If round has ended, move dealer
+            self.move_dealer_and_increase_blind();
+            self.round_number = 0;
+            // Reset fold state
+            for seat in &mut self.seats {
+                if !seat.is_out {
+                    seat.has_folded = false;
+                }
+            }
+        }
+    }
+
+    /// Moves the dealer to the next player and increases the blind.
+    /// The variant rotates along with the dealer at the start of the next hand.
+    fn move_dealer_and_increase_blind(&mut self) {
+        self.hands_with_variant += 1;
+        // If no one remains, this will not be an infinity loop
+        for _ in 0..self.seats.len() {
+            self.dealer += 1;
+            self.dealer %= self.seats.len();
+            if self.dealer == 0 {
+                // Increase blind if dealer passes position 0
+                self.small_blind *= 2;
+            }
+            if !self.seats[self.dealer].is_out {
+                // If person which can deal is found, stop
+                break;
+            }
+        }
+    }
+
+    /// Queries all players who can play the current round in a list.
+    /// The queried list is in correct playing order.
+    fn get_qualified_players(&mut self) {
+        self.players_in_round.clear();
+        let mut cur = self.dealer;
+        // Dealer is the last one in round
+        for _ in 0..self.seats.len() {
+            cur += 1;
+            cur %= self.seats.len();
+
+            // If player has neither folded nor is out nor sits out, player is in
+            let seat = &self.seats[cur];
+            if !seat.is_out && !seat.has_folded && !seat.is_sitting_out {
+                self.players_in_round.push(cur);
+            }
+        }
+    }
+}
+
+/// Derives the seed of a hand from the seed of the table and the number of the hand.
+/// Together with the actions of the players, the seed determines every card dealt in the hand.
+pub fn hand_seed(table_seed: u64, hand_number: usize) -> u64 {
+    // SplitMix64, which spreads consecutive hand numbers over all seeds
+    let mut z = table_seed.wrapping_add((hand_number as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Calculates how much every player receives from the pot, given the bets and the winners of every board.
+/// Every board pays an equal share of every bet, the first board also pays the remainder.
+/// If there is a low, the share is split in a high and a low half, the odd chip goes to the high half.
+/// The part of the low half which the low winners can not win, as they are all in for less, goes to the high winners.
+/// Whatever the winners can not win is returned to the players.
+fn calculate_payouts(bets: &[usize], board_winners: &[BoardWinners]) -> Vec<usize> {
+    let mut payouts = vec![0; bets.len()];
+    for (board_index, winners) in board_winners.iter().enumerate() {
+        let shares: Vec<usize> = bets
+            .iter()
+            .map(|bet| {
+                let remainder = if board_index == 0 {
+                    bet % board_winners.len()
+                } else {
+                    0
+                };
+                bet / board_winners.len() + remainder
+            })
+            .collect();
+        let unwon = if winners.low.is_empty() {
+            pay_winners(&shares, &winners.high, &mut payouts)
+        } else {
+            let low_shares: Vec<usize> = shares.iter().map(|share| share / 2).collect();
+            let high_shares: Vec<usize> = shares
+                .iter()
+                .zip(&low_shares)
+                .map(|(share, low_share)| share - low_share)
+                .collect();
+            let high_unwon = pay_winners(&high_shares, &winners.high, &mut payouts);
+            let low_unwon = pay_winners(&low_shares, &winners.low, &mut payouts);
+            let low_unwon = pay_winners(&low_unwon, &winners.high, &mut payouts);
+            high_unwon
+                .iter()
+                .zip(low_unwon)
+                .map(|(high, low)| high + low)
+                .collect()
+        };
+        // Return coins to players
+        for (payout, share) in payouts.iter_mut().zip(unwon) {
+            *payout += share;
+        }
+    }
+    payouts
+}
+
+/// Splits the shares of all players evenly among the winners and adds them to the payouts.
+/// A winner wins no more than he has bet from every player, the rest is split among the remaining winners.
+/// The first winner receives the odd chips. Returns what is left of the shares, which no winner could win.
+fn pay_winners(shares: &[usize], winners: &[usize], payouts: &mut [usize]) -> Vec<usize> {
+    let mut levels: Vec<usize> = winners.iter().map(|&w| shares[w]).collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut previous_level = 0;
+    for level in levels {
+        // Might bet less than others in "all in" case
+        // Most cases won't be affected by this
+        let layer: usize = shares
+            .iter()
+            .map(|&share| share.min(level) - share.min(previous_level))
+            .sum();
+        let eligible: Vec<usize> = winners
+            .iter()
+            .copied()
+            .filter(|&w| shares[w] >= level)
+            .collect();
+        for &w in &eligible {
+            payouts[w] += layer / eligible.len();
+        }
+        payouts[eligible[0]] += layer % eligible.len();
+        previous_level = level;
+    }
+
+    shares
+        .iter()
+        .map(|share| share.saturating_sub(previous_level))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Utility function for creating the settings of a heads up Texas Hold'em table.
+    fn settings() -> TableSettings {
+        TableSettings {
+            variant: GameVariant::TexasHoldem,
+            betting: BettingStructure::NoLimit,
+            rotation: Rotation::Fixed,
+            straddles: StraddleRule::Off,
+            small_blind: 1,
+            start_money: 100,
+            max_run_outs: 1,
+            rabbit_hunt_interval: None,
+            bomb_pot: None,
+            is_shuffle_audited: false,
+        }
+    }
+
+    /// Utility function for finding the player who has to bet next.
+    fn next_bettor(events: &[Event]) -> Option<usize> {
+        events.iter().rev().find_map(|event| match event {
+            Event::AwaitingBet { player, .. } => Some(*player),
+            _ => None,
+        })
+    }
+
+    /// Utility function for playing until the showdown, where every player checks or calls.
+    fn check_down(engine: &mut Engine) -> Vec<Event> {
+        let mut log = engine.start();
+        let mut events = log.clone();
+        while !events.contains(&Event::Wait(SHOWDOWN_DELAY)) {
+            let player = next_bettor(&events).expect("Failed: No player has to bet");
+            events = engine.apply(Action::Choice {
+                player,
+                choice: PlayerChoice::Pass,
+            });
+            log.extend(events.iter().cloned());
+        }
+        log
+    }
+
+    /// Utility function for starting the game with a fixed deck, whose last card is dealt first.
+    fn start_with_deck(engine: &mut Engine, deck: Vec<Card>) -> Vec<Event> {
+        let mut events = vec![];
+        engine.small_blind = engine.settings.small_blind;
+        for seat in &mut engine.seats {
+            seat.coins = engine.settings.start_money;
+        }
+        engine.get_qualified_players();
+        engine.hand_number = 1;
+        engine.card_stack = deck;
+        engine.deal_hand(&mut events);
+        events
+    }
+
+    /// Utility function for playing the current hand, where every player folds, until the next hand starts.
+    fn fold_hand(engine: &mut Engine, mut events: Vec<Event>) -> Vec<Event> {
+        let hand = engine.hand_number;
+        while engine.hand_number == hand {
+            events = match next_bettor(&events) {
+                Some(player) => engine.apply(Action::Choice {
+                    player,
+                    choice: PlayerChoice::Fold,
+                }),
+                None => engine.apply(Action::Timeout),
+            };
+        }
+        events
+    }
+
+    /// Testing that the hand ends once everyone else folded.
+    #[test]
+    fn test_fold_ends_hand() {
+        let mut engine = Engine::new(settings(), 2, 0);
+        let events = engine.start();
+        // The player after the dealer pays the small blind and acts first
+        assert_eq!(Some(1), next_bettor(&events));
+        let events = engine.apply(Action::Choice {
+            player: 1,
+            choice: PlayerChoice::Fold,
+        });
+        // Every player of the round is asked, even if he is the last one left
+        assert_eq!(Some(0), next_bettor(&events));
+        let events = engine.apply(Action::Choice {
+            player: 0,
+            choice: PlayerChoice::Pass,
+        });
+        assert!(events.contains(&Event::StateChanged {
+            hand_winner: Some(0),
+            is_showdown: true,
+        }));
+        assert_eq!(Some(&Event::Wait(EARLY_END_DELAY)), events.last());
+        let coins: Vec<usize> = engine.seats().iter().map(Seat::coins).collect();
+        assert_eq!(vec![101, 99], coins);
+
+        // Actions of players the engine does not wait for are ignored
+        assert!(engine
+            .apply(Action::Choice {
+                player: 0,
+                choice: PlayerChoice::Pass,
+            })
+            .is_empty());
+
+        // The dealer moves on to the next hand
+        let events = engine.apply(Action::Timeout);
+        assert_eq!(Some(0), next_bettor(&events));
+        let bets: Vec<usize> = engine
+            .seats()
+            .iter()
+            .map(Seat::current_betting_amount)
+            .collect();
+        assert_eq!(vec![1, 2], bets);
+    }
+
+    /// Testing that the hand log tells everything that happened in a hand.
+    #[test]
+    fn test_hand_log() {
+        let mut engine = Engine::new(settings(), 2, 0);
+        let mut events = engine.start();
+        events.extend(engine.apply(Action::Choice {
+            player: 1,
+            choice: PlayerChoice::RaiseTo(10),
+        }));
+        events.extend(engine.apply(Action::Choice {
+            player: 0,
+            choice: PlayerChoice::Fold,
+        }));
+        let log: Vec<HandEvent> = events
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::Logged(event) => Some(event),
+                _ => None,
+            })
+            .collect();
+        let cards = |player: usize| match &log[player + 1] {
+            HandEvent::CardsDealt { cards, .. } => cards.clone(),
+            event => panic!("Failed: {:?}. Expected dealt cards", event),
+        };
+        let expected = vec![
+            HandEvent::HandStarted {
+                hand_number: 1,
+                seed: Some(hand_seed(0, 1)),
+                variant: GameVariant::TexasHoldem,
+                betting: BettingStructure::NoLimit,
+                dealer: 0,
+                small_blind: 1,
+                players: vec![1, 0],
+                stacks: vec![100, 100],
+            },
+            HandEvent::CardsDealt {
+                player: 1,
+                cards: cards(0),
+                up_cards: vec![],
+            },
+            HandEvent::CardsDealt {
+                player: 0,
+                cards: cards(1),
+                up_cards: vec![],
+            },
+            HandEvent::BlindPosted {
+                player: 1,
+                kind: ForcedBet::SmallBlind,
+                amount: 1,
+            },
+            HandEvent::BlindPosted {
+                player: 0,
+                kind: ForcedBet::BigBlind,
+                amount: 2,
+            },
+            HandEvent::ActionTaken {
+                player: 1,
+                action: Move::Raise,
+                amount: 9,
+                total: 10,
+                is_all_in: false,
+            },
+            HandEvent::ActionTaken {
+                player: 0,
+                action: Move::Fold,
+                amount: 0,
+                total: 2,
+                is_all_in: false,
+            },
+            HandEvent::BetReturned {
+                player: 1,
+                amount: 8,
+            },
+            HandEvent::PotAwarded {
+                player: 1,
+                amount: 4,
+            },
+            HandEvent::HandEnded {
+                hand_number: 1,
+                stacks: vec![98, 102],
+            },
+        ];
+        assert_eq!(expected, log);
+    }
+
+    /// Testing that a checked down hand is paid at the showdown, the same way for the same seed.
+    #[test]
+    fn test_check_down() {
+        let mut engine = Engine::new(settings(), 3, 42);
+        let log = check_down(&mut engine);
+        assert_eq!(5, engine.open_middle_cards().len());
+        assert!(log.iter().any(|event| matches!(
+            event,
+            Event::StateChanged {
+                is_showdown: true,
+                ..
+            }
+        )));
+        // The board and the hands of the players who could win are logged
+        let board: Vec<Card> = log
+            .iter()
+            .filter_map(|event| match event {
+                Event::Logged(HandEvent::BoardDealt { board: 0, cards }) => Some(cards.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert_eq!(engine.open_middle_cards(), board);
+        assert!(log
+            .iter()
+            .any(|event| matches!(event, Event::Logged(HandEvent::HandShown { .. }))));
+        // The pot is paid and the next hand starts
+        let events = engine.apply(Action::Timeout);
+        assert_eq!(Some(2), next_bettor(&events));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::Logged(HandEvent::HandEnded { hand_number: 1, .. })
+        )));
+        let coins: usize = engine
+            .seats()
+            .iter()
+            .map(|seat| seat.coins() + seat.current_betting_amount())
+            .sum();
+        assert_eq!(300, coins);
+
+        let mut replay = Engine::new(settings(), 3, 42);
+        assert_eq!(log, check_down(&mut replay));
+    }
+
+    /// Testing that a game restored from a checkpoint continues exactly like the original game.
+    #[test]
+    fn test_restore() {
+        let mut engine = Engine::new(settings(), 3, 11);
+        let mut events = engine.start();
+        let mut restored: Option<Engine> = None;
+        while !events.iter().any(|e| matches!(e, Event::GameEnd(_))) {
+            let checkpoint = events.iter().position(|e| match e {
+                Event::Checkpoint(state) => state.hand_number == 2,
+                _ => false,
+            });
+            if let Some(position) = checkpoint {
+                let Event::Checkpoint(state) = events[position].clone() else {
+                    unreachable!()
+                };
+                let mut engine = Engine::restore(state.clone());
+                assert_eq!(state, engine.state());
+                assert_eq!(events[position..].to_vec(), engine.resume(&[]));
+                restored = Some(engine);
+            }
+            // The first player to act raises, everyone else calls
+            let action = match next_bettor(&events) {
+                Some(player) => {
+                    let choice = match engine
+                        .seats()
+                        .iter()
+                        .all(|s| s.current_betting_amount() <= 2)
+                    {
+                        true => PlayerChoice::RaiseTo(10),
+                        false => PlayerChoice::Pass,
+                    };
+                    Action::Choice { player, choice }
+                }
+                None => Action::Timeout,
+            };
+            if let Some(restored) = &mut restored {
+                let restored_events = restored.apply(action.clone());
+                events = engine.apply(action);
+                assert_eq!(events, restored_events);
+            } else {
+                events = engine.apply(action);
+            }
+        }
+        assert!(restored.is_some_and(|engine| engine.hand_number() > 2));
+    }
+
+    /// Testing that players who did not return to a restored table are not dealt in and keep their stacks.
+    #[test]
+    fn test_restore_with_absent_player() {
+        let events = Engine::new(settings(), 3, 5).start();
+        let Some(Event::Checkpoint(state)) = events.first().cloned() else {
+            panic!(
+                "Failed: Expected a checkpoint before the first hand, got {:?}",
+                events
+            );
+        };
+        let mut engine = Engine::restore(state);
+        let mut events = engine.resume(&[1]);
+        let mut hands = 0;
+        while hands < 3 && !events.iter().any(|e| matches!(e, Event::GameEnd(_))) {
+            for event in &events {
+                if let Event::Logged(HandEvent::HandStarted { players, .. }) = event {
+                    assert!(!players.contains(&1), "Failed: Absent player was dealt in");
+                    hands += 1;
+                }
+            }
+            // Everyone who is there goes all in
+            let action = match next_bettor(&events) {
+                Some(player) => Action::Choice {
+                    player,
+                    choice: PlayerChoice::RaiseTo(usize::MAX),
+                },
+                None => Action::Timeout,
+            };
+            events = engine.apply(action);
+        }
+        assert!(hands > 0);
+        assert_eq!(100, engine.seats()[1].coins());
+        assert_eq!(0, engine.seats()[1].current_betting_amount());
+    }
+
+    /// Testing that a hand is dealt the same way for the same table seed, no matter how the previous hands went.
+    #[test]
+    fn test_hand_seeds() {
+        let mut folded = Engine::new(settings(), 3, 37);
+        let mut events = folded.start();
+        // Everyone folds in the first hand
+        while folded.hand_number() < 2 {
+            events = match next_bettor(&events) {
+                Some(player) => folded.apply(Action::Choice {
+                    player,
+                    choice: PlayerChoice::Fold,
+                }),
+                None => folded.apply(Action::Timeout),
+            };
+        }
+        let mut checked = Engine::new(settings(), 3, 37);
+        check_down(&mut checked);
+        checked.apply(Action::Timeout);
+
+        assert_eq!(2, checked.hand_number());
+        for (folded_seat, checked_seat) in folded.seats().iter().zip(checked.seats()) {
+            assert_eq!(folded_seat.cards, checked_seat.cards);
+        }
+        assert_ne!(hand_seed(37, 1), hand_seed(37, 2));
+        assert_ne!(hand_seed(37, 1), hand_seed(38, 1));
+    }
+
+    /// Testing that the players can check the auditable shuffle of a hand once it is revealed.
+    #[test]
+    fn test_audited_shuffle() {
+        let mut settings = settings();
+        settings.is_shuffle_audited = true;
+        let mut engine = Engine::new(settings, 2, 0).with_secret([3; 32]);
+        let events = engine.start();
+        // The checkpoint before the hand comes first
+        let commitment = match &events[1] {
+            Event::ShuffleCommitted {
+                hand_number: 1,
+                commitment,
+            } => commitment.clone(),
+            e => panic!("Failed: Expected a commitment, got {:?}", e),
+        };
+        assert_eq!(Some(&Event::Wait(ENTROPY_TIMEOUT)), events.last());
+
+        // Seeds which are too long are ignored
+        let long_seed = "x".repeat(shuffle::MAX_CLIENT_SEED_LENGTH + 1);
+        let events = engine.apply(Action::ClientSeed {
+            player: 0,
+            seed: long_seed,
+        });
+        assert!(events.is_empty());
+        engine.apply(Action::ClientSeed {
+            player: 1,
+            seed: "abc".to_string(),
+        });
+        let events = engine.apply(Action::ClientSeed {
+            player: 0,
+            seed: "def".to_string(),
+        });
+        assert_eq!(Some(1), next_bettor(&events));
+        let hands: Vec<Vec<Card>> = engine.seats().iter().map(|s| s.cards.clone()).collect();
+
+        engine.apply(Action::Choice {
+            player: 1,
+            choice: PlayerChoice::Fold,
+        });
+        let events = engine.apply(Action::Choice {
+            player: 0,
+            choice: PlayerChoice::Pass,
+        });
+        let (server_seed, client_seeds) = events
+            .iter()
+            .find_map(|event| match event {
+                Event::ShuffleRevealed {
+                    hand_number: 1,
+                    server_seed,
+                    client_seeds,
+                } => Some((server_seed.clone(), client_seeds.clone())),
+                _ => None,
+            })
+            .expect("Failed: The shuffle was not revealed");
+        assert_eq!(
+            vec![Some("def".to_string()), Some("abc".to_string())],
+            client_seeds
+        );
+
+        // The player after the dealer is dealt first
+        let deck = GameVariant::TexasHoldem.deck();
+        let mut dealt = shuffle::verify(deck, &commitment, &server_seed, &client_seeds).unwrap();
+        let first: Vec<Card> = (0..2).map(|_| dealt.pop().unwrap()).collect();
+        let second: Vec<Card> = (0..2).map(|_| dealt.pop().unwrap()).collect();
+        assert_eq!(vec![second, first], hands);
+    }
+
+    /// Testing that the betting of bomb pots starts on the flop.
+    #[test]
+    fn test_bomb_pot() {
+        let mut settings = settings();
+        settings.bomb_pot = Some(BombPotSettings {
+            ante: 5,
+            interval: Some(1),
+            is_voted: false,
+            is_double_board: true,
+        });
+        let mut engine = Engine::new(settings, 3, 0);
+        let events = engine.start();
+        assert_eq!(Some(1), next_bettor(&events));
+        assert!(engine.is_bomb_pot());
+        assert_eq!(2, engine.round_number());
+        assert_eq!(3, engine.open_middle_cards().len());
+        assert_eq!(3, engine.extra_boards()[0].len());
+        assert!(engine
+            .seats()
+            .iter()
+            .all(|seat| seat.current_betting_amount() == 5));
+    }
+
+    /// Testing that a voted bomb pot needs the votes of every player in time.
+    #[test]
+    fn test_bomb_pot_vote() {
+        let yes = |player: usize| Action::Choice {
+            player,
+            choice: PlayerChoice::BombPot(true),
+        };
+        let no = |player: usize| Action::Choice {
+            player,
+            choice: PlayerChoice::BombPot(false),
+        };
+        let test_cases = vec![
+            (vec![yes(1), yes(2), yes(0)], true),
+            (vec![yes(1), no(2)], false),
+            (vec![yes(1), yes(2), Action::Timeout], false),
+            (vec![yes(1), Action::Disconnect { player: 0 }], false),
+        ];
+        for (votes, expected) in test_cases {
+            let mut settings = settings();
+            settings.bomb_pot = Some(BombPotSettings {
+                ante: 5,
+                interval: None,
+                is_voted: true,
+                is_double_board: false,
+            });
+            let mut engine = Engine::new(settings, 3, 0);
+            let events = engine.start();
+            assert!(events.contains(&Event::AwaitingBombPotVote {
+                players: vec![1, 2, 0]
+            }));
+            let mut events = vec![];
+            for vote in votes.iter().cloned() {
+                events = engine.apply(vote);
+            }
+            assert_eq!(expected, engine.is_bomb_pot(), "Failed: Votes {:?}", votes);
+            assert!(next_bettor(&events).is_some(), "Failed: Votes {:?}", votes);
+            let bets: Vec<usize> = engine
+                .seats()
+                .iter()
+                .map(Seat::current_betting_amount)
+                .collect();
+            let expected_bets = if expected {
+                vec![5, 5, 5]
+            } else {
+                vec![0, 1, 2]
+            };
+            assert_eq!(expected_bets, bets, "Failed: Votes {:?}", votes);
+        }
+    }
+
+    /// Testing that only hands whose number is a multiple of the interval are bomb pots.
+    #[test]
+    fn test_bomb_pot_interval() {
+        let mut settings = settings();
+        settings.bomb_pot = Some(BombPotSettings {
+            ante: 5,
+            interval: Some(3),
+            is_voted: false,
+            is_double_board: false,
+        });
+        let mut engine = Engine::new(settings, 3, 0);
+        let mut events = engine.start();
+        let mut hands = vec![];
+        for _ in 0..4 {
+            let hand = engine.hand_number;
+            hands.push((hand, engine.is_bomb_pot()));
+            // Everyone folds
+            while engine.hand_number == hand {
+                events = match next_bettor(&events) {
+                    Some(player) => engine.apply(Action::Choice {
+                        player,
+                        choice: PlayerChoice::Fold,
+                    }),
+                    None => engine.apply(Action::Timeout),
+                };
+            }
+        }
+        assert_eq!(vec![(1, false), (2, false), (3, true), (4, false)], hands);
+    }
+
+    /// Testing who may straddle, how much, and who acts first and last before the flop afterwards.
+    #[test]
+    fn test_straddles() {
+        // Player 0 is on the button, 1 and 2 pay the blinds, 3 may straddle first
+        let test_cases = vec![
+            // A single straddle acts last
+            (
+                StraddleRule::Single,
+                vec![true],
+                vec![(3, 4)],
+                vec![0, 1, 2, 4],
+                vec![0, 1, 2, 3],
+            ),
+            // The button re-straddles the straddle
+            (
+                StraddleRule::Restraddle,
+                vec![true, true],
+                vec![(3, 4), (0, 8)],
+                vec![8, 1, 2, 4],
+                vec![1, 2, 3, 0],
+            ),
+            // The first player who declines to re-straddle acts first
+            (
+                StraddleRule::Restraddle,
+                vec![true, false],
+                vec![(3, 4), (0, 8)],
+                vec![0, 1, 2, 4],
+                vec![0, 1, 2, 3],
+            ),
+            // The button may straddle if the player after the big blind did not
+            (
+                StraddleRule::Single,
+                vec![false, true],
+                vec![(3, 4), (0, 4)],
+                vec![4, 1, 2, 0],
+                vec![1, 2, 3, 0],
+            ),
+            // Without a straddle the usual order stays, which starts with the small blind
+            (
+                StraddleRule::Restraddle,
+                vec![false, false],
+                vec![(3, 4), (0, 4)],
+                vec![0, 1, 2, 0],
+                vec![1, 2, 3, 0],
+            ),
+        ];
+        for (rule, decisions, expected_offers, expected_bets, expected_order) in test_cases {
+            let mut settings = settings();
+            settings.straddles = rule;
+            let mut engine = Engine::new(settings, 4, 0);
+            let mut events = engine.start();
+            let mut offers = vec![];
+            for decision in &decisions {
+                let offer = events.iter().find_map(|event| match event {
+                    Event::AwaitingStraddle { player, amount } => Some((*player, *amount)),
+                    _ => None,
+                });
+                let (player, amount) = offer.expect("Failed: No straddle offered");
+                offers.push((player, amount));
+                events = engine.apply(Action::Choice {
+                    player,
+                    choice: PlayerChoice::Straddle(*decision),
+                });
+            }
+            assert!(
+                !events
+                    .iter()
+                    .any(|event| matches!(event, Event::AwaitingStraddle { .. })),
+                "Failed: {:?} {:?}. Expected no more straddles",
+                rule,
+                decisions
+            );
+            let bets: Vec<usize> = engine
+                .seats()
+                .iter()
+                .map(Seat::current_betting_amount)
+                .collect();
+            // Everyone calls until the flop
+            let mut order = vec![];
+            while engine.round_number() == 0 {
+                let player = next_bettor(&events).expect("Failed: No player has to bet");
+                order.push(player);
+                events = engine.apply(Action::Choice {
+                    player,
+                    choice: PlayerChoice::Pass,
+                });
+            }
+            assert_eq!(
+                (
+                    expected_offers.clone(),
+                    expected_bets.clone(),
+                    expected_order.clone()
+                ),
+                (offers.clone(), bets.clone(), order.clone()),
+                "Failed: {:?} {:?}. Expected {:?} {:?} {:?}, got {:?} {:?} {:?}",
+                rule,
+                decisions,
+                expected_offers,
+                expected_bets,
+                expected_order,
+                offers,
+                bets,
+                order
+            );
+        }
+    }
+
+    /// Testing that a rabbit hunt reveals the cards the board would have been dealt,
+    /// at most once per interval of hands for every player who played the hand.
+    #[test]
+    fn test_rabbit_hunt() {
+        let settings = TableSettings {
+            rabbit_hunt_interval: Some(2),
+            ..settings()
+        };
+        // The board a check down deals from the same deck
+        let mut checked = Engine::new(settings.clone(), 2, 11);
+        check_down(&mut checked);
+        let board = checked.open_middle_cards().to_vec();
+        assert_eq!(5, board.len());
+
+        // The players who may hunt in hands 1 to 3 and the players who ask
+        let cases = vec![
+            (vec![1], vec![1]),
+            (vec![0], vec![1, 0]),
+            (vec![1], vec![1]),
+        ];
+        let mut engine = Engine::new(settings, 2, 11);
+        let mut events = engine.start();
+        for (hand, (expected, hunters)) in cases.into_iter().enumerate() {
+            // The first player to act folds, the other one wins
+            let player = next_bettor(&events).expect("Failed: No player has to bet");
+            events = engine.apply(Action::Choice {
+                player,
+                choice: PlayerChoice::Fold,
+            });
+            let player = next_bettor(&events).expect("Failed: No player has to bet");
+            engine.apply(Action::Choice {
+                player,
+                choice: PlayerChoice::Pass,
+            });
+            let mut result = vec![];
+            for player in hunters {
+                for event in engine.apply(Action::RabbitHunt { player }) {
+                    if let Event::RabbitHuntResult {
+                        requested_by,
+                        cards,
+                    } = event
+                    {
+                        if hand == 0 {
+                            assert_eq!(board, cards, "Failed: Runout differs from the deck");
+                        }
+                        result.push(requested_by);
+                    }
+                }
+            }
+            assert_eq!(expected, result, "Failed: Hand {}", hand + 1);
+            events = engine.apply(Action::Timeout);
+        }
+    }
+
+    /// Testing that only players who were dealt into the hand may hunt.
+    #[test]
+    fn test_rabbit_hunt_without_playing() {
+        let settings = TableSettings {
+            rabbit_hunt_interval: Some(1),
+            ..settings()
+        };
+        let mut engine = Engine::new(settings, 3, 5);
+        // The last player lost all coins before
+        engine.seats[2].is_out = true;
+        let mut events = engine.start();
+        let player = next_bettor(&events).expect("Failed: No player has to bet");
+        events = engine.apply(Action::Choice {
+            player,
+            choice: PlayerChoice::Fold,
+        });
+        let player = next_bettor(&events).expect("Failed: No player has to bet");
+        engine.apply(Action::Choice {
+            player,
+            choice: PlayerChoice::Pass,
+        });
+        assert!(
+            engine.apply(Action::RabbitHunt { player: 2 }).is_empty(),
+            "Failed: Player who was out could hunt"
+        );
+        assert!(engine
+            .apply(Action::RabbitHunt { player })
+            .iter()
+            .any(|event| matches!(event, Event::RabbitHuntResult { .. })));
+    }
+
+    /// Testing that the equities are shown once every remaining player is all in.
+    #[test]
+    fn test_all_in_equities() {
+        let cards =
+            |s: &str| -> Vec<Card> { s.split(' ').map(|c| Card::try_from(c).unwrap()).collect() };
+        let mut engine = Engine::new(settings(), 2, 0);
+        let mut events = engine.start();
+        // Three aces against three kings, which only the last king on the river wins
+        engine.seats[0].cards = cards("AC BC");
+        engine.seats[1].cards = cards("AB BB");
+        let dealt = cards("AC BC AB BB DB B0 A5 CB CC");
+        let mut deck: Vec<Card> = GameVariant::TexasHoldem
+            .deck()
+            .into_iter()
+            .filter(|card| !dealt.contains(card))
+            .collect();
+        deck.extend(&dealt[4..]);
+        engine.card_stack = deck;
+
+        while engine.open_middle_cards.len() < 4 {
+            let player = next_bettor(&events).expect("Failed: No player has to bet");
+            events = engine.apply(Action::Choice {
+                player,
+                choice: PlayerChoice::Pass,
+            });
+        }
+        assert_eq!(cards("CC CB A5 B0"), engine.open_middle_cards);
+        assert!(engine.seats().iter().all(|seat| seat.equity.is_none()));
+
+        let player = next_bettor(&events).expect("Failed: No player has to bet");
+        engine.apply(Action::Choice {
+            player,
+            choice: PlayerChoice::RaiseTo(usize::MAX),
+        });
+        let events = engine.apply(Action::Choice {
+            player: 1 - player,
+            choice: PlayerChoice::Pass,
+        });
+        assert!(events.contains(&Event::StateChanged {
+            hand_winner: None,
+            is_showdown: false,
+        }));
+        assert_eq!(Some(&Event::Wait(ALL_IN_STREET_DELAY)), events.last());
+        let equities: Vec<f64> = engine
+            .seats()
+            .iter()
+            .map(|seat| seat.equity.expect("Failed: Equity missing"))
+            .collect();
+        for (expected, equity) in [4300.0 / 44.0, 100.0 / 44.0].iter().zip(&equities) {
+            assert!(
+                (expected - equity).abs() < 1e-9,
+                "Failed: Expected {}, got {:?}",
+                expected,
+                equities
+            );
+        }
+
+        // The river is dealt from the same deck
+        engine.apply(Action::Timeout);
+        assert_eq!(cards("CC CB A5 B0 DB"), engine.open_middle_cards);
+    }
+
+    /// Testing that the players of Pineapple variants discard a hole card on the right street,
+    /// the last one if they do not choose in time.
+    #[test]
+    fn test_discard() {
+        // The variant and the board cards open when the players discard
+        let cases = vec![
+            (GameVariant::Pineapple, 0),
+            (GameVariant::CrazyPineapple, 3),
+        ];
+        for (variant, board) in cases {
+            let settings = TableSettings {
+                variant,
+                ..settings()
+            };
+            let mut engine = Engine::new(settings, 2, 3);
+            let mut events = engine.start();
+            while !events.contains(&Event::AwaitingDiscard {
+                players: vec![1, 0],
+            }) {
+                let player = next_bettor(&events).expect("Failed: No player has to bet");
+                events = engine.apply(Action::Choice {
+                    player,
+                    choice: PlayerChoice::Pass,
+                });
+            }
+            assert_eq!(
+                board,
+                engine.open_middle_cards.len(),
+                "Failed: {:?} discards on the wrong street",
+                variant
+            );
+            let hand = |engine: &Engine, player: usize| engine.seats()[player].cards.clone();
+            assert_eq!(3, hand(&engine, 0).len());
+
+            // Cards the player does not hold can not be discarded
+            let card = hand(&engine, 1)[0];
+            assert!(
+                engine
+                    .apply(Action::Choice {
+                        player: 0,
+                        choice: PlayerChoice::Discard(card),
+                    })
+                    .is_empty(),
+                "Failed: {:?} discards a card of the opponent",
+                variant
+            );
+            assert_eq!(3, hand(&engine, 0).len());
+
+            let mut expected = hand(&engine, 0);
+            let card = expected.remove(0);
+            engine.apply(Action::Choice {
+                player: 0,
+                choice: PlayerChoice::Discard(card),
+            });
+            assert_eq!(expected, hand(&engine, 0));
+
+            // The other player discards his last card once the time is up
+            let mut expected = hand(&engine, 1);
+            expected.pop();
+            engine.apply(Action::Timeout);
+            assert_eq!(expected, hand(&engine, 1));
+            assert_eq!(
+                board + if board == 0 { 3 } else { 1 },
+                engine.open_middle_cards.len(),
+                "Failed: {:?} deals no board after the discard",
+                variant
+            );
+        }
+    }
+
+    /// Testing that the worst door card brings in and the best face up cards act first in stud games,
+    /// which deal the last card face down and award the pot to the best high hand or, in Razz, the best low hand.
+    #[test]
+    fn test_stud_hand() {
+        let cards =
+            |s: &str| -> Vec<Card> { s.split(' ').map(|c| Card::try_from(c).unwrap()).collect() };
+        // The cards of every player in the order they are dealt: two face down, the door card, four more streets.
+        // The player who brings in, the first players to act on every street and the winner.
+        let cases = vec![
+            (
+                GameVariant::Stud,
+                [
+                    "A5 D8 D0 BB DB A2 CB",
+                    "B4 CA C1 A1 B5 C3 D9",
+                    "AC DA A0 C7 A7 B7 D3",
+                ],
+                2,
+                vec![0, 1, 0, 2, 2],
+                0,
+            ),
+            (
+                GameVariant::Razz,
+                [
+                    "AC C1 A4 B4 B0 A6 C2",
+                    "C7 D8 CA A0 AB C3 D5",
+                    "DC D3 DB B1 A2 A1 A5",
+                ],
+                2,
+                vec![0, 1, 2, 1, 1],
+                0,
+            ),
+        ];
+        for (variant, hands, bring_in, first_players, winner) in cases {
+            let hands: Vec<Vec<Card>> = hands.iter().map(|hand| cards(hand)).collect();
+            // Every street is dealt in the order the previous street was played, starting after the dealer
+            let order = |first: usize| (0..3).map(move |i| (first + i) % 3);
+            let mut dealt: Vec<Card> = order(1).flat_map(|p| hands[p][..3].to_vec()).collect();
+            for (street, first) in first_players[..4].iter().enumerate() {
+                dealt.extend(order(*first).map(|p| hands[p][street + 3]));
+            }
+            let mut deck: Vec<Card> = variant
+                .deck()
+                .into_iter()
+                .filter(|card| !dealt.contains(card))
+                .collect();
+            deck.extend(dealt.iter().rev());
+            let settings = TableSettings {
+                variant,
+                betting: variant.default_betting_structure(),
+                ..settings()
+            };
+            let mut engine = Engine::new(settings, 3, 0);
+            let mut events = start_with_deck(&mut engine, deck);
+
+            let players: Vec<usize> = order(1).collect();
+            let door_cards: Vec<Card> = players.iter().map(|p| hands[*p][2]).collect();
+            let index = variant.bring_in(&door_cards).unwrap();
+            assert_eq!(bring_in, players[index]);
+            assert!(
+                events.contains(&Event::Logged(HandEvent::BlindPosted {
+                    player: bring_in,
+                    kind: ForcedBet::BringIn,
+                    amount: 1,
+                })),
+                "Failed: {:?} brings in with the wrong player",
+                variant
+            );
+
+            let mut first = vec![];
+            while !events.contains(&Event::Wait(SHOWDOWN_DELAY)) {
+                let player = next_bettor(&events).expect("Failed: No player has to bet");
+                if first.len() <= engine.round_number / 2 {
+                    first.push(player);
+                }
+                events = engine.apply(Action::Choice {
+                    player,
+                    choice: PlayerChoice::Pass,
+                });
+            }
+            assert_eq!(
+                first_players, first,
+                "Failed: {:?} lets the wrong players act first",
+                variant
+            );
+            for (player, hand) in hands.iter().enumerate() {
+                let seat = &engine.seats()[player];
+                assert_eq!(hand, &seat.cards);
+                // The last card is dealt face down
+                assert_eq!(hand[2..6], seat.up_cards);
+            }
+            assert!(
+                events.contains(&Event::StateChanged {
+                    hand_winner: Some(winner),
+                    is_showdown: true,
+                }),
+                "Failed: {:?} awards the pot to the wrong player",
+                variant
+            );
+        }
+    }
+
+    /// Testing that six players of 2-7 Triple Draw can exchange all their cards three times,
+    /// as the cards exchanged before are reshuffled once the deck runs out, except those of the drawing player.
+    #[test]
+    fn test_triple_draw() {
+        let settings = TableSettings {
+            variant: GameVariant::DeuceToSevenTripleDraw,
+            betting: BettingStructure::FixedLimit,
+            ..settings()
+        };
+        let mut engine = Engine::new(settings, 6, 0);
+        let mut events = engine.start();
+        let mut log = events.clone();
+        while !events.contains(&Event::Wait(SHOWDOWN_DELAY)) {
+            let drawing = events.iter().find_map(|event| match event {
+                Event::AwaitingDraw { players } => Some(players.clone()),
+                _ => None,
+            });
+            let actions: Vec<Action> = match drawing {
+                Some(players) => players
+                    .iter()
+                    .map(|&player| Action::Choice {
+                        player,
+                        choice: PlayerChoice::Draw(engine.seats()[player].cards.clone()),
+                    })
+                    .collect(),
+                None => vec![Action::Choice {
+                    player: next_bettor(&events).expect("Failed: No player has to bet"),
+                    choice: PlayerChoice::Pass,
+                }],
+            };
+            for action in actions {
+                events = engine.apply(action);
+                log.extend(events.iter().cloned());
+            }
+        }
+
+        let mut discarded_before = vec![];
+        let mut reshuffled = 0;
+        let mut draws = 0;
+        for event in &log {
+            if let Event::Logged(HandEvent::CardsDrawn {
+                player,
+                discarded,
+                drawn,
+            }) = event
+            {
+                draws += 1;
+                assert_eq!(5, drawn.len());
+                assert!(
+                    drawn.iter().all(|card| !discarded.contains(card)),
+                    "Failed: Player {} draws back the discarded cards {:?}",
+                    player,
+                    discarded
+                );
+                reshuffled += drawn
+                    .iter()
+                    .filter(|card| discarded_before.contains(*card))
+                    .count();
+                discarded_before.extend(discarded);
+            }
+        }
+        assert_eq!(18, draws);
+        assert!(reshuffled > 0, "Failed: No cards were reshuffled");
+        let hands: Vec<Card> = engine
+            .seats()
+            .iter()
+            .flat_map(|seat| seat.cards.clone())
+            .collect();
+        assert_eq!(30, hands.len());
+        assert!(hands.iter().map(|card| card.value).all_unique());
+    }
+
+    /// Testing that players of 2-7 Triple Draw who do not choose their cards in time keep their hand.
+    #[test]
+    fn test_draw_timeout() {
+        let settings = TableSettings {
+            variant: GameVariant::DeuceToSevenTripleDraw,
+            betting: BettingStructure::FixedLimit,
+            ..settings()
+        };
+        let mut engine = Engine::new(settings, 2, 0);
+        let mut events = engine.start();
+        while !events.contains(&Event::AwaitingDraw {
+            players: vec![1, 0],
+        }) {
+            let player = next_bettor(&events).expect("Failed: No player has to bet");
+            events = engine.apply(Action::Choice {
+                player,
+                choice: PlayerChoice::Pass,
+            });
+        }
+        let hand = |engine: &Engine, player: usize| engine.seats()[player].cards.clone();
+
+        // Cards the player does not hold can not be exchanged
+        let cards = hand(&engine, 1)[..2].to_vec();
+        engine.apply(Action::Choice {
+            player: 0,
+            choice: PlayerChoice::Draw(cards),
+        });
+        let cards = hand(&engine, 0)[..2].to_vec();
+        engine.apply(Action::Choice {
+            player: 0,
+            choice: PlayerChoice::Draw(cards.clone()),
+        });
+
+        let kept = hand(&engine, 1);
+        let events = engine.apply(Action::Timeout);
+        assert_eq!(kept, hand(&engine, 1));
+        assert!(events.contains(&Event::Logged(HandEvent::CardsDrawn {
+            player: 1,
+            discarded: vec![],
+            drawn: vec![],
+        })));
+        let exchanged = hand(&engine, 0);
+        assert!(exchanged[..2].iter().all(|card| !cards.contains(card)));
+        assert!(events.contains(&Event::Logged(HandEvent::CardsDrawn {
+            player: 0,
+            discarded: cards,
+            drawn: exchanged[..2].to_vec(),
+        })));
+        assert_eq!(Some(1), next_bettor(&events));
+    }
+
+    /// Testing that HORSE plays every variant for the amount of hands, all of them with fixed-limit betting.
+    #[test]
+    fn test_horse() {
+        let settings = TableSettings {
+            betting: BettingStructure::FixedLimit,
+            rotation: Rotation::Horse {
+                hands_per_variant: 2,
+            },
+            ..settings()
+        };
+        let mut engine = Engine::new(settings, 3, 0);
+        let mut events = engine.start();
+        let mut hands = vec![];
+        for _ in 0..5 {
+            let settings = engine.settings();
+            hands.push((
+                settings.variant,
+                settings.betting,
+                engine.next_variant_change(),
+            ));
+            events = fold_hand(&mut engine, events);
+        }
+        let fixed_limit = BettingStructure::FixedLimit;
+        let expected = vec![
+            (
+                GameVariant::TexasHoldem,
+                fixed_limit,
+                Some((GameVariant::OmahaHiLo, 2)),
+            ),
+            (
+                GameVariant::TexasHoldem,
+                fixed_limit,
+                Some((GameVariant::OmahaHiLo, 1)),
+            ),
+            (
+                GameVariant::OmahaHiLo,
+                fixed_limit,
+                Some((GameVariant::Razz, 2)),
+            ),
+            (
+                GameVariant::OmahaHiLo,
+                fixed_limit,
+                Some((GameVariant::Razz, 1)),
+            ),
+            (GameVariant::Razz, fixed_limit, Some((GameVariant::Stud, 2))),
+        ];
+        assert_eq!(expected, hands);
+    }
+
+    /// Testing that the player on the button chooses the variant and its betting structure in dealer's choice,
+    /// and that the variant does not change unless it is chosen in time.
+    #[test]
+    fn test_dealers_choice() {
+        let settings = TableSettings {
+            rotation: Rotation::DealersChoice,
+            ..settings()
+        };
+        let mut engine = Engine::new(settings, 3, 0);
+        let choose = |player: usize, variant: &str| Action::Choice {
+            player,
+            choice: PlayerChoice::ChooseVariant(variant.to_string()),
+        };
+        // The button, the choice (or a timeout), the variant and the betting structure of the hand
+        let cases = vec![
+            (
+                0,
+                Some("plo"),
+                GameVariant::PotLimitOmaha,
+                BettingStructure::PotLimit,
+            ),
+            (
+                1,
+                None,
+                GameVariant::PotLimitOmaha,
+                BettingStructure::PotLimit,
+            ),
+            (
+                2,
+                Some("razz"),
+                GameVariant::Razz,
+                BettingStructure::FixedLimit,
+            ),
+        ];
+        let mut events = engine.start();
+        for (button, choice, variant, betting) in cases {
+            assert!(
+                events.contains(&Event::AwaitingVariant {
+                    player: button,
+                    choices: GameVariant::ALL.to_vec(),
+                }),
+                "Failed: Player {} does not choose the variant",
+                button
+            );
+            assert_eq!(Some(&Event::Wait(VARIANT_TIMEOUT)), events.last());
+            // Only the button chooses, and only variants which exist
+            assert!(engine.apply(choose((button + 1) % 3, "plo")).is_empty());
+            assert!(engine.apply(choose(button, "chess")).is_empty());
+
+            events = match choice {
+                Some(choice) => engine.apply(choose(button, choice)),
+                None => engine.apply(Action::Timeout),
+            };
+            assert_eq!(variant, engine.settings().variant);
+            assert_eq!(betting, engine.settings().betting);
+            assert!(events.iter().any(|event| matches!(
+                event,
+                Event::Logged(HandEvent::HandStarted { variant: v, .. }) if *v == variant
+            )));
+            events = fold_hand(&mut engine, events);
+        }
+    }
+
+    /// Testing that the game ends once a player left.
+    #[test]
+    fn test_disconnect() {
+        let mut engine = Engine::new(settings(), 2, 0);
+        engine.start();
+        // The player who has to bet folds
+        let events = engine.apply(Action::Disconnect { player: 1 });
+        assert_eq!(Some(0), next_bettor(&events));
+        let events = engine.apply(Action::Choice {
+            player: 0,
+            choice: PlayerChoice::Pass,
+        });
+        assert_eq!(Some(&Event::Wait(EARLY_END_DELAY)), events.last());
+        let events = engine.apply(Action::Timeout);
+        assert_eq!(Some(&Event::GameEnd(Some(0))), events.last());
+    }
+
+    /// Utility function for creating the winners of a board.
+    fn winners(high: &[usize], low: &[usize]) -> BoardWinners {
+        BoardWinners {
+            high: high.to_vec(),
+            low: low.to_vec(),
+        }
+    }
+
+    /// Testing the payout of a single board.
+    #[test]
+    #[rustfmt::skip]
+    fn test_single_board_payouts() {
+        let test_cases = vec![
+            // Winner takes all
+            (vec![100, 100, 50], vec![winners(&[0], &[])], vec![250, 0, 0]),
+            // All in winner only wins as much as he has bet from every player
+            (vec![100, 100, 50], vec![winners(&[2], &[])], vec![50, 50, 150]),
+            // If no one wins, the pot is returned
+            (vec![100, 100], vec![winners(&[], &[])], vec![100, 100]),
+        ];
+        for (bets, board_winners, expected) in test_cases {
+            let payouts = calculate_payouts(&bets, &board_winners);
+            assert_eq!(
+                expected, payouts,
+                "Failed: {:?} {:?}. Expected {:?}, got {:?}",
+                bets, board_winners, expected, payouts
+            );
+        }
+    }
+
+    /// Testing the payout of a pot which was run out several times.
+    #[test]
+    #[rustfmt::skip]
+    fn test_multiple_board_payouts() {
+        let test_cases = vec![
+            // Both boards are won by the same player
+            (
+                vec![100, 100],
+                vec![winners(&[0], &[]), winners(&[0], &[])],
+                vec![200, 0],
+            ),
+            // Boards are split
+            (
+                vec![100, 100],
+                vec![winners(&[0], &[]), winners(&[1], &[])],
+                vec![100, 100],
+            ),
+            // The first board pays the remainder
+            (
+                vec![101, 101],
+                vec![winners(&[0], &[]), winners(&[1], &[])],
+                vec![102, 100],
+            ),
+            // A tie on one board returns that share
+            (
+                vec![90, 90, 90],
+                vec![winners(&[2], &[]), winners(&[], &[]), winners(&[2], &[])],
+                vec![30, 30, 210],
+            ),
+        ];
+        for (bets, board_winners, expected) in test_cases {
+            let payouts = calculate_payouts(&bets, &board_winners);
+            assert_eq!(
+                expected, payouts,
+                "Failed: {:?} {:?}. Expected {:?}, got {:?}",
+                bets, board_winners, expected, payouts
+            );
+            assert_eq!(bets.iter().sum::<usize>(), payouts.iter().sum::<usize>());
+        }
+    }
+
+    /// Testing the payout of split and high-low pots.
+    #[test]
+    #[rustfmt::skip]
+    fn test_hi_lo_payouts() {
+        let test_cases = vec![
+            // Scoop: one player wins both halves
+            (vec![100, 100], vec![winners(&[0], &[0])], vec![200, 0]),
+            // High and low are won by different players, the odd chip goes to the high half
+            (vec![101, 100, 100], vec![winners(&[0], &[1])], vec![151, 150, 0]),
+            // A tied high is split evenly, the odd chip goes to the first winner
+            (vec![101, 101, 101], vec![winners(&[2, 1], &[])], vec![0, 151, 152]),
+            // Quartering: the high hand also ties for the low half
+            (vec![100, 100, 100], vec![winners(&[0], &[0, 1])], vec![225, 75, 0]),
+            // An all in winner shares only the part of the pot he could win
+            (vec![100, 50, 100], vec![winners(&[0, 1], &[])], vec![175, 75, 0]),
+            // The high winner wins the rest of the low half, which the all in low winner can not win
+            (vec![100, 20, 100], vec![winners(&[0], &[1])], vec![190, 30, 0]),
+            // The rest of the low half is returned if the high winner is all in for less as well
+            (vec![20, 10, 100], vec![winners(&[0], &[1])], vec![35, 15, 80]),
+        ];
+        for (bets, board_winners, expected) in test_cases {
+            let payouts = calculate_payouts(&bets, &board_winners);
+            assert_eq!(
+                expected, payouts,
+                "Failed: {:?} {:?}. Expected {:?}, got {:?}",
+                bets, board_winners, expected, payouts
+            );
+            assert_eq!(bets.iter().sum::<usize>(), payouts.iter().sum::<usize>());
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use crate::cards::Card;
+use crate::variant::GameVariant;
+use itertools::Itertools;
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Maximal number of board run-outs which are enumerated exhaustively.
+/// Calculations with more possible run-outs fall back to a Monte Carlo simulation.
+pub const EXHAUSTIVE_LIMIT: usize = 50_000;
+
+/// Default number of simulated run-outs of a Monte Carlo calculation.
+pub const DEFAULT_ITERATIONS: usize = 20_000;
+
+/// The amount of cards on a complete board.
+pub(crate) const BOARD_SIZE: usize = 5;
+
+/// The chances of a single player, all values in percent.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Equity {
+    /// Run-outs the player wins the whole pot alone.
+    pub win: f64,
+
+    /// Run-outs the player wins a part of the pot, sharing it with at least one other player.
+    pub tie: f64,
+
+    /// Run-outs the player wins nothing.
+    pub lose: f64,
+
+    /// Expected share of the pot, where a split pot is shared evenly among the winners.
+    pub share: f64,
+}
+
+/// The result of an equity calculation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EquityResult {
+    /// The equity of every hand, in the order the hands were provided.
+    pub equities: Vec<Equity>,
+
+    /// Whether all possible run-outs were enumerated or the result is a Monte Carlo estimate.
+    pub is_exhaustive: bool,
+
+    /// The amount of evaluated run-outs.
+    pub run_outs: usize,
+}
+
+/// Accumulates the weighted outcomes of the evaluated run-outs of a single player.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Tally {
+    wins: f64,
+    ties: f64,
+    losses: f64,
+    share: f64,
+}
+
+impl Tally {
+    /// Converts the tally into an equity, given the total weight of all evaluated run-outs.
+    pub(crate) fn to_equity(self, total_weight: f64) -> Equity {
+        let percent = |amount: f64| 100.0 * amount / total_weight;
+        Equity {
+            win: percent(self.wins),
+            tie: percent(self.ties),
+            lose: percent(self.losses),
+            share: percent(self.share),
+        }
+    }
+}
+
+/// Calculates the equity of the provided Texas Hold'em hands.
+///
+/// Every hand consists of two known hole cards. The board may be partially dealt (0, 3, 4 or 5 cards,
+/// any amount up to 5 is accepted) and dead cards are removed from the deck.
+/// If at most `EXHAUSTIVE_LIMIT` run-outs remain, all of them are enumerated.
+/// Otherwise `iterations` run-outs are simulated with a random generator seeded by `seed`,
+/// so the same input always yields the same result.
+///
+/// ```
+/// use nolimittexasholdem::equity::calculate_equity;
+/// use nolimittexasholdem::Card;
+///
+/// let cards = |s: &str| -> Vec<Card> { s.split(' ').map(|c| Card::try_from(c).unwrap()).collect() };
+/// let hands = [cards("AC BC"), cards("CB DB")];
+/// let result = calculate_equity(&hands, &cards("A0 B5 C9"), &[], 1000, 1).unwrap();
+/// // Only a king on the turn or the river saves the kings
+/// assert!(result.is_exhaustive);
+/// assert!(result.equities[0].share > 90.0);
+/// ```
+pub fn calculate_equity(
+    hands: &[Vec<Card>],
+    board: &[Card],
+    dead: &[Card],
+    iterations: usize,
+    seed: u64,
+) -> Result<EquityResult, String> {
+    calculate_variant_equity(
+        GameVariant::TexasHoldem,
+        hands,
+        board,
+        dead,
+        iterations,
+        seed,
+    )
+}
+
+/// Calculates the equity of the provided hands according to the rules of the variant.
+///
+/// Works like `calculate_equity`, but every hand consists of as many hole cards as the variant has at the showdown.
+/// In stud games, hands may be incomplete, their missing cards are dealt in every run-out as well.
+pub fn calculate_variant_equity(
+    variant: GameVariant,
+    hands: &[Vec<Card>],
+    board: &[Card],
+    dead: &[Card],
+    iterations: usize,
+    seed: u64,
+) -> Result<EquityResult, String> {
+    if hands.is_empty() {
+        return Err("No hands provided".into());
+    }
+    let hole_cards = variant.showdown_hole_cards();
+    let is_valid = |hand: &&Vec<Card>| {
+        hand.len() == hole_cards
+            || (variant.is_stud() && !hand.is_empty() && hand.len() < hole_cards)
+    };
+    if let Some(hand) = hands.iter().find(|hand| !is_valid(hand)) {
+        return Err(format!(
+            "Hand must contain {} cards: {:?}",
+            hole_cards, hand
+        ));
+    }
+    let board_size = variant.board_cards();
+    if board.len() > board_size {
+        return Err(format!("Board has too many cards: {}", board.len()));
+    }
+
+    let deck = remaining_deck(variant, hands.iter().flatten().chain(board).chain(dead))?;
+    let missing_board = board_size - board.len();
+    let missing_hands: Vec<usize> = hands.iter().map(|hand| hole_cards - hand.len()).collect();
+    let missing = missing_board + missing_hands.iter().sum::<usize>();
+    if deck.len() < missing {
+        return Err("Not enough cards left to complete the board".into());
+    }
+
+    let mut tallies = vec![Tally::default(); hands.len()];
+    let mut run_out = board.to_vec();
+    // Only the board is enumerated, incomplete hands are always simulated
+    let is_exhaustive =
+        missing == missing_board && binomial(deck.len(), missing) <= EXHAUSTIVE_LIMIT;
+    let run_outs = if is_exhaustive {
+        let mut count = 0;
+        for drawn in deck.iter().combinations(missing) {
+            run_out.truncate(board.len());
+            run_out.extend(drawn);
+            evaluate_run_out(variant, hands, &run_out, 1.0, &mut tallies);
+            count += 1;
+        }
+        count
+    } else {
+        if iterations == 0 {
+            return Err("At least one iteration is required".into());
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut deck = deck;
+        let mut completed_hands = hands.to_vec();
+        for _ in 0..iterations {
+            let (drawn, _) = deck.partial_shuffle(&mut rng, missing);
+            let (drawn_board, mut drawn_hands) = drawn.split_at(missing_board);
+            run_out.truncate(board.len());
+            run_out.extend_from_slice(drawn_board);
+            for ((completed, hand), amount) in
+                completed_hands.iter_mut().zip(hands).zip(&missing_hands)
+            {
+                let (dealt, rest) = drawn_hands.split_at(*amount);
+                completed.truncate(hand.len());
+                completed.extend_from_slice(dealt);
+                drawn_hands = rest;
+            }
+            evaluate_run_out(variant, &completed_hands, &run_out, 1.0, &mut tallies);
+        }
+        iterations
+    };
+
+    let equities = tallies
+        .iter()
+        .map(|tally| tally.to_equity(run_outs as f64))
+        .collect();
+
+    Ok(EquityResult {
+        equities,
+        is_exhaustive,
+        run_outs,
+    })
+}
+
+/// Returns all cards of the deck of the variant which are not among the provided known cards.
+/// Fails if a known card is not part of the deck or appears more than once.
+pub(crate) fn remaining_deck<'a>(
+    variant: GameVariant,
+    known: impl Iterator<Item = &'a Card>,
+) -> Result<Vec<Card>, String> {
+    let deck = variant.deck();
+    let mut is_known = [false; 52];
+    for card in known {
+        if !deck.contains(card) {
+            return Err(format!("Invalid card: {}", card.value));
+        }
+        let index = card.value as usize;
+        if is_known[index] {
+            return Err(format!("Card {} appears more than once", card));
+        }
+        is_known[index] = true;
+    }
+    Ok(deck
+        .into_iter()
+        .filter(|card| !is_known[card.value as usize])
+        .collect())
+}
+
+/// Evaluates all hands against a complete board and adds the outcome, weighted by `weight`, to the tallies.
+pub(crate) fn evaluate_run_out(
+    variant: GameVariant,
+    hands: &[impl AsRef<[Card]>],
+    board: &[Card],
+    weight: f64,
+    tallies: &mut [Tally],
+) {
+    let values: Vec<usize> = hands
+        .iter()
+        .map(|hand| variant.value_of_hand(hand.as_ref(), board))
+        .collect();
+    let low_values: Vec<Option<usize>> = hands
+        .iter()
+        .map(|hand| variant.low_value_of_hand(hand.as_ref(), board))
+        .collect();
+
+    let mut shares = pot_shares(&values);
+    if low_values.iter().any(Option::is_some) {
+        // The pot is split between the high and the low hands
+        let low_shares = pot_shares(&low_values);
+        for (share, low_share) in shares.iter_mut().zip(low_shares) {
+            *share = (*share + low_share) / 2.0;
+        }
+    }
+    for (tally, share) in tallies.iter_mut().zip(shares) {
+        if share == 0.0 {
+            tally.losses += weight;
+        } else if share == 1.0 {
+            tally.wins += weight;
+        } else {
+            tally.ties += weight;
+        }
+        tally.share += weight * share;
+    }
+}
+
+/// Returns the share of the pot of every hand, where the pot is shared evenly among the best hands.
+fn pot_shares<T: Ord>(values: &[T]) -> Vec<f64> {
+    let best = values.iter().max().unwrap();
+    let winners = values.iter().filter(|&value| value == best).count();
+    values
+        .iter()
+        .map(|value| {
+            if value == best {
+                1.0 / winners as f64
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Calculates the binomial coefficient, saturating at `usize::MAX`.
+pub(crate) fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    let mut result: usize = 1;
+    for i in 0..k {
+        result = match result.checked_mul(n - i) {
+            Some(product) => product / (i + 1),
+            None => return usize::MAX,
+        };
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Utility function for parsing a string of cards.
+    fn parse(s: &str) -> Vec<Card> {
+        s.split_whitespace()
+            .map(Card::try_from)
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    /// Testing a complete board, where only one run-out is possible.
+    #[test]
+    fn test_complete_board() {
+        let hands = vec![parse("AC BC"), parse("AB BB"), parse("A0 B0")];
+        let result =
+            calculate_equity(&hands, &parse("CC C1 D5 A7 B9"), &[], DEFAULT_ITERATIONS, 0).unwrap();
+        assert!(result.is_exhaustive);
+        assert_eq!(result.run_outs, 1);
+        assert_eq!(result.equities[0].win, 100.0);
+        assert_eq!(result.equities[1].lose, 100.0);
+        assert_eq!(result.equities[2].lose, 100.0);
+    }
+
+    /// Testing a split pot on the board.
+    #[test]
+    fn test_split_pot() {
+        let hands = vec![parse("A0 B1"), parse("C0 D1")];
+        let result =
+            calculate_equity(&hands, &parse("AC BB C9 D8 A7"), &[], DEFAULT_ITERATIONS, 0).unwrap();
+        for equity in result.equities {
+            assert_eq!(equity.tie, 100.0);
+            assert_eq!(equity.share, 50.0);
+        }
+    }
+
+    /// Testing the exhaustive enumeration of a flop, including dead cards.
+    #[test]
+    fn test_exhaustive_flop() {
+        let hands = vec![parse("AC BC"), parse("AB BB")];
+        let result = calculate_equity(&hands, &parse("C0 D4 A7"), &parse("D9"), 1, 0).unwrap();
+        assert!(result.is_exhaustive);
+        // 52 - 4 hole cards - 3 board cards - 1 dead card, choose 2
+        assert_eq!(result.run_outs, 44 * 43 / 2);
+        let shares: f64 = result.equities.iter().map(|e| e.share).sum();
+        assert!((shares - 100.0).abs() < 1e-9);
+        for equity in &result.equities {
+            assert!((equity.win + equity.tie + equity.lose - 100.0).abs() < 1e-9);
+        }
+        // Aces against kings with two cards to come: kings need one of the two remaining kings,
+        // but lose if the other card is one of the two remaining aces
+        let kings_win = (2.0 * 42.0 + 1.0 - 2.0 * 2.0) / (44.0 * 43.0 / 2.0) * 100.0;
+        assert!((result.equities[1].win - kings_win).abs() < 1e-9);
+    }
+
+    /// Testing the Monte Carlo fallback for a preflop all in.
+    #[test]
+    fn test_monte_carlo_preflop() {
+        // Aces against kings win roughly 82% of the time
+        let hands = vec![parse("AC BC"), parse("AB BB")];
+        let result = calculate_equity(&hands, &[], &[], DEFAULT_ITERATIONS, 42).unwrap();
+        assert!(!result.is_exhaustive);
+        assert_eq!(result.run_outs, DEFAULT_ITERATIONS);
+        assert!((result.equities[0].share - 82.0).abs() < 2.0);
+
+        let repeated = calculate_equity(&hands, &[], &[], DEFAULT_ITERATIONS, 42).unwrap();
+        assert_eq!(result, repeated);
+    }
+
+    /// Testing failure of invalid inputs.
+    #[test]
+    fn test_invalid_input() {
+        let test_cases = vec![
+            (vec![], parse("")),
+            (vec![parse("AC")], parse("")),
+            (vec![parse("AC BC"), parse("AC BB")], parse("")),
+            (vec![parse("AC BC")], parse("A0 A1 A2 A3 A4 A5")),
+            (vec![parse("AC BC")], parse("A0 BC")),
+        ];
+        for (hands, board) in test_cases {
+            assert!(
+                calculate_equity(&hands, &board, &[], DEFAULT_ITERATIONS, 0).is_err(),
+                "Failed: {:?} {:?}. Expected Error",
+                hands,
+                board
+            );
+        }
+    }
+}
@@ -0,0 +1,1217 @@
+use crate::betting::BettingStructure;
+use crate::cards::Card;
+use crate::hand_log::{ForcedBet, HandEvent, Move};
+use crate::variant::GameVariant;
+use itertools::Itertools;
+use serde_json::{json, Value};
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::Path;
+
+/// The most players a table seats.
+const MAX_SEATS: usize = 6;
+
+/// Names of the boards of a hand which is run out more than once.
+const BOARD_NAMES: [&str; 6] = ["FIRST", "SECOND", "THIRD", "FOURTH", "FIFTH", "SIXTH"];
+
+/// The JSON schema of hand histories in JSON format, which have one hand per line.
+pub const JSON_SCHEMA: &str = include_str!("../hand_history.schema.json");
+
+/// The formats hand histories are written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// The text format of PokerStars, as tracking software imports it.
+    PokerStars,
+
+    /// JSON Lines as described by `JSON_SCHEMA`, for analysis and replays.
+    Json,
+}
+
+impl TryFrom<&str> for HistoryFormat {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "pokerstars" => Ok(Self::PokerStars),
+            "json" => Ok(Self::Json),
+            v => Err(format!("No hand history format: {}", v)),
+        }
+    }
+}
+
+impl fmt::Display for HistoryFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::PokerStars => "pokerstars",
+            Self::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A hand as it is exported for tracking software: the events of the hand log,
+/// together with what the log does not know about the table.
+///
+/// In JSON, the events are written as in the hand log:
+///
+/// ```
+/// use nolimittexasholdem::hand_history::HandHistory;
+/// use nolimittexasholdem::hand_log::HandEvent;
+///
+/// let history = HandHistory {
+///     table: "Table 8080".to_string(),
+///     hand_id: 1,
+///     started_at: 0,
+///     player_names: vec!["Alice".to_string(), "Bob".to_string()],
+///     events: vec![HandEvent::PotAwarded { player: 1, amount: 3 }],
+/// };
+/// let json = history.to_json();
+/// assert_eq!(
+///     r#"{"events":[{"amount":3,"player":1,"type":"pot_awarded"}],"hand_id":1,"player_names":["Alice","Bob"],"started_at":0,"table":"Table 8080"}"#,
+///     json
+/// );
+/// assert_eq!(Ok(vec![history]), HandHistory::from_json_lines(&json));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandHistory {
+    /// The name of the table.
+    pub table: String,
+
+    /// The number identifying the hand, which should be unique across all tables and games.
+    pub hand_id: u64,
+
+    /// The start of the hand in seconds since the Unix epoch.
+    pub started_at: u64,
+
+    /// The names of the players by seat.
+    pub player_names: Vec<String>,
+
+    /// The events of the hand log, from `HandEvent::HandStarted` to `HandEvent::HandEnded`.
+    pub events: Vec<HandEvent>,
+}
+
+impl HandHistory {
+    /// Returns the name of the player at the seat, which is never empty.
+    pub fn player_name(&self, player: usize) -> String {
+        match self.player_names.get(player) {
+            Some(name) if !name.is_empty() => name.clone(),
+            _ => format!("Player {}", player + 1),
+        }
+    }
+
+    /// Writes the hand in the text format of PokerStars hand histories.
+    /// Only the hole cards of the hero are shown, or the hole cards of every player if there is no hero.
+    /// Seats are numbered from 1 and all times are UTC.
+    pub fn to_pokerstars(&self, hero: Option<usize>) -> String {
+        PokerStarsWriter::new(self, hero).write()
+    }
+
+    /// Writes the hand as a single line of JSON, which shows the hole cards of every player.
+    pub fn to_json(&self) -> String {
+        Value::from(self.clone()).to_string()
+    }
+
+    /// Reads the hands of a hand history in JSON Lines. Empty lines are skipped.
+    pub fn from_json_lines(text: &str) -> Result<Vec<Self>, String> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let value: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+                Self::try_from(value).map_err(|e| format!("Line {}: {}", i + 1, e))
+            })
+            .collect()
+    }
+
+    /// Reads the hands of the hand history in JSON Lines at the path.
+    pub fn read_json(path: impl AsRef<Path>) -> Result<Vec<Self>, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json_lines(&text)
+    }
+}
+
+impl From<HandHistory> for Value {
+    fn from(history: HandHistory) -> Self {
+        let events: Vec<Value> = history.events.into_iter().map(Value::from).collect();
+        json!({
+            "table": history.table,
+            "hand_id": history.hand_id,
+            "started_at": history.started_at,
+            "player_names": history.player_names,
+            "events": events
+        })
+    }
+}
+
+impl TryFrom<Value> for HandHistory {
+    type Error = String;
+
+    fn try_from(mut value: Value) -> Result<Self, Self::Error> {
+        let number = |key: &str| {
+            value[key]
+                .as_u64()
+                .ok_or_else(|| format!("Missing field: {}", key))
+        };
+        let hand_id = number("hand_id")?;
+        let started_at = number("started_at")?;
+        let table = value["table"]
+            .as_str()
+            .ok_or("Missing field: table")?
+            .to_string();
+        let player_names = value["player_names"]
+            .as_array()
+            .ok_or("Missing field: player_names")?
+            .iter()
+            .map(|name| {
+                name.as_str()
+                    .map(str::to_string)
+                    .ok_or(format!("No name: {}", name))
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+        let events = match value["events"].take() {
+            Value::Array(events) => events
+                .into_iter()
+                .map(HandEvent::try_from)
+                .collect::<Result<Vec<HandEvent>, String>>()?,
+            _ => return Err("Missing field: events".into()),
+        };
+        Ok(Self {
+            table,
+            hand_id,
+            started_at,
+            player_names,
+            events,
+        })
+    }
+}
+
+/// Writes a hand history line by line, keeping track of the streets and bets.
+struct PokerStarsWriter<'a> {
+    history: &'a HandHistory,
+    hero: Option<usize>,
+    variant: GameVariant,
+    lines: Vec<String>,
+
+    /// The current street, starting with 0 before the flop, third street or the first draw.
+    street: usize,
+
+    /// The players who were dealt cards or drew on the current street.
+    acted_on_street: Vec<usize>,
+
+    /// The total bet of every player in the hand and at the start of the current street.
+    totals: Vec<usize>,
+    street_starts: Vec<usize>,
+
+    /// The cards of every player and of every board.
+    cards: Vec<Vec<Card>>,
+    up_cards: Vec<Vec<Card>>,
+    boards: Vec<Vec<Card>>,
+
+    /// What happened to every player, for the summary.
+    blinds: Vec<Option<ForcedBet>>,
+    folded_on: Vec<Option<usize>>,
+    shown: Vec<Option<Vec<Card>>>,
+    won: Vec<usize>,
+    is_showdown: bool,
+}
+
+impl<'a> PokerStarsWriter<'a> {
+    fn new(history: &'a HandHistory, hero: Option<usize>) -> Self {
+        let seats = match history.events.first() {
+            Some(HandEvent::HandStarted { stacks, .. }) => stacks.len(),
+            _ => history.player_names.len(),
+        };
+        let variant = match history.events.first() {
+            Some(HandEvent::HandStarted { variant, .. }) => *variant,
+            _ => GameVariant::TexasHoldem,
+        };
+        Self {
+            history,
+            hero,
+            variant,
+            lines: vec![],
+            street: 0,
+            acted_on_street: vec![],
+            totals: vec![0; seats],
+            street_starts: vec![0; seats],
+            cards: vec![vec![]; seats],
+            up_cards: vec![vec![]; seats],
+            boards: vec![vec![]],
+            blinds: vec![None; seats],
+            folded_on: vec![None; seats],
+            shown: vec![None; seats],
+            won: vec![0; seats],
+            is_showdown: false,
+        }
+    }
+
+    /// Whether the hole cards of the player may be shown.
+    fn is_visible(&self, player: usize) -> bool {
+        self.hero.is_none_or(|hero| hero == player)
+    }
+
+    fn name(&self, player: usize) -> String {
+        self.history.player_name(player)
+    }
+
+    fn write(mut self) -> String {
+        let history = self.history;
+        let events = &history.events;
+        if let Some(event) = events.first() {
+            self.write_header(event);
+        }
+        // Blinds are posted before the cards are dealt, only the bring-in follows them
+        for event in events {
+            match event {
+                HandEvent::BlindPosted {
+                    player,
+                    kind,
+                    amount,
+                } if *kind != ForcedBet::BringIn => self.write_blind(*player, *kind, *amount),
+                _ => {}
+            }
+        }
+        let mut is_dealing = true;
+        for (i, event) in events.iter().enumerate() {
+            match event {
+                HandEvent::HandStarted { .. } => { /* Already written */ }
+                HandEvent::BlindPosted { kind, .. } if *kind != ForcedBet::BringIn => {
+                    /* Already written */
+                }
+                HandEvent::CardsDealt {
+                    player,
+                    cards,
+                    up_cards,
+                } => {
+                    if is_dealing {
+                        self.write_hole_cards(*player, cards, up_cards);
+                    } else {
+                        self.write_stud_street(*player, cards, up_cards);
+                    }
+                }
+                _ if is_dealing => {
+                    is_dealing = false;
+                    self.write_dealt_cards();
+                    self.write_event(event, events.get(i + 1));
+                }
+                event => self.write_event(event, events.get(i + 1)),
+            }
+        }
+        self.lines.push(String::new());
+        self.lines.join("\n")
+    }
+
+    fn write_header(&mut self, event: &HandEvent) {
+        let HandEvent::HandStarted {
+            betting,
+            dealer,
+            small_blind,
+            players,
+            stacks,
+            ..
+        } = event
+        else {
+            return;
+        };
+        let (small, big) = match betting {
+            BettingStructure::FixedLimit => (2 * small_blind, 4 * small_blind),
+            _ => (*small_blind, 2 * small_blind),
+        };
+        let betting = match betting {
+            BettingStructure::NoLimit => "No Limit",
+            BettingStructure::PotLimit => "Pot Limit",
+            BettingStructure::FixedLimit => "Limit",
+        };
+        self.lines.push(format!(
+            "PokerStars Hand #{}:  {} {} ({}/{}) - {} UTC",
+            self.history.hand_id,
+            game_name(self.variant),
+            betting,
+            small,
+            big,
+            format_time(self.history.started_at)
+        ));
+        let button = if self.variant.is_stud() {
+            String::new()
+        } else {
+            format!(" Seat #{} is the button", dealer + 1)
+        };
+        self.lines.push(format!(
+            "Table '{}' {}-max{}",
+            self.history.table, MAX_SEATS, button
+        ));
+        for player in players.iter().sorted() {
+            self.lines.push(format!(
+                "Seat {}: {} ({} in chips)",
+                player + 1,
+                self.name(*player),
+                stacks[*player]
+            ));
+        }
+    }
+
+    fn write_blind(&mut self, player: usize, kind: ForcedBet, amount: usize) {
+        let name = self.name(player);
+        self.lines.push(match kind {
+            ForcedBet::SmallBlind => format!("{}: posts small blind {}", name, amount),
+            ForcedBet::BigBlind => format!("{}: posts big blind {}", name, amount),
+            ForcedBet::Straddle => format!("{}: posts straddle {}", name, amount),
+            ForcedBet::BringIn => format!("{}: brings in for {}", name, amount),
+            ForcedBet::Ante => format!("{}: posts the ante {}", name, amount),
+        });
+        self.blinds[player] = Some(kind);
+        self.totals[player] = amount;
+    }
+
+    fn write_hole_cards(&mut self, player: usize, cards: &[Card], up_cards: &[Card]) {
+        self.cards[player] = cards.to_vec();
+        self.up_cards[player] = up_cards.to_vec();
+    }
+
+    /// Writes the cards dealt before the first action.
+    fn write_dealt_cards(&mut self) {
+        self.lines.push(if self.variant.is_stud() {
+            "*** 3rd STREET ***".to_string()
+        } else if self.variant.draws() > 0 {
+            "*** DEALING HANDS ***".to_string()
+        } else {
+            "*** HOLE CARDS ***".to_string()
+        });
+        for player in 0..self.cards.len() {
+            let cards = if self.is_visible(player) {
+                &self.cards[player]
+            } else if self.variant.is_stud() {
+                &self.up_cards[player]
+            } else {
+                continue;
+            };
+            if !cards.is_empty() {
+                self.lines.push(format!(
+                    "Dealt to {} [{}]",
+                    self.name(player),
+                    format_cards(cards)
+                ));
+            }
+        }
+    }
+
+    /// Writes a card dealt on a later street of a stud game.
+    fn write_stud_street(&mut self, player: usize, cards: &[Card], up_cards: &[Card]) {
+        if self.acted_on_street.contains(&player) || self.acted_on_street.is_empty() {
+            self.start_street();
+            let street = self.street + 3;
+            self.lines.push(if street >= 7 {
+                "*** RIVER ***".to_string()
+            } else {
+                format!("*** {}th STREET ***", street)
+            });
+        }
+        self.acted_on_street.push(player);
+        let is_visible = self.is_visible(player);
+        let (known, new) = if is_visible {
+            (&self.cards[player], cards)
+        } else {
+            (&self.up_cards[player], up_cards)
+        };
+        if !new.is_empty() {
+            self.lines.push(format!(
+                "Dealt to {} [{}] [{}]",
+                self.name(player),
+                format_cards(known),
+                format_cards(new)
+            ));
+        }
+        self.cards[player].extend(cards);
+        self.up_cards[player].extend(up_cards);
+    }
+
+    /// Starts the next street, on which the bets start anew.
+    fn start_street(&mut self) {
+        self.street += 1;
+        self.acted_on_street.clear();
+        self.street_starts = self.totals.clone();
+    }
+
+    fn write_event(&mut self, event: &HandEvent, next: Option<&HandEvent>) {
+        match event {
+            HandEvent::ActionTaken {
+                player,
+                action,
+                amount,
+                total,
+                is_all_in,
+            } => self.write_action(*player, *action, *amount, *total, *is_all_in),
+            HandEvent::CardDiscarded { player, card } => {
+                let mut line = format!("{}: discards 1 card", self.name(*player));
+                if self.is_visible(*player) {
+                    line += &format!(" [{}]", format_cards(&[*card]));
+                }
+                self.lines.push(line);
+                self.cards[*player].retain(|c| c != card);
+            }
+            HandEvent::CardsDrawn {
+                player,
+                discarded,
+                drawn,
+            } => self.write_draw(*player, discarded, drawn),
+            HandEvent::BoardDealt { board, cards } => {
+                let is_run_out =
+                    matches!(next, Some(HandEvent::BoardDealt { board, .. }) if *board > 0);
+                self.write_board(*board, cards, is_run_out);
+            }
+            HandEvent::HandShown { player, cards } => {
+                if !self.is_showdown {
+                    self.is_showdown = true;
+                    self.lines.push("*** SHOW DOWN ***".to_string());
+                }
+                self.lines.push(format!(
+                    "{}: shows [{}]",
+                    self.name(*player),
+                    format_cards(cards)
+                ));
+                self.shown[*player] = Some(cards.clone());
+            }
+            HandEvent::BetReturned { player, amount } => {
+                self.lines.push(format!(
+                    "Uncalled bet ({}) returned to {}",
+                    amount,
+                    self.name(*player)
+                ));
+            }
+            HandEvent::PotAwarded { player, amount } => {
+                self.lines.push(format!(
+                    "{} collected {} from pot",
+                    self.name(*player),
+                    amount
+                ));
+                self.won[*player] += amount;
+            }
+            HandEvent::BlindPosted {
+                player,
+                kind,
+                amount,
+            } => self.write_blind(*player, *kind, *amount),
+            HandEvent::HandEnded { .. } => self.write_summary(),
+            HandEvent::HandStarted { .. } | HandEvent::CardsDealt { .. } => { /* Written before */ }
+            // Neither the table, the inputs of the engine nor the revealed shuffle are part of the hand history
+            HandEvent::TableOpened { .. }
+            | HandEvent::TableRestored { .. }
+            | HandEvent::ActionApplied { .. }
+            | HandEvent::TableClosed { .. }
+            | HandEvent::ShuffleRevealed { .. } => {}
+        }
+    }
+
+    fn write_action(
+        &mut self,
+        player: usize,
+        action: Move,
+        amount: usize,
+        total: usize,
+        is_all_in: bool,
+    ) {
+        let name = self.name(player);
+        let highest = (0..self.totals.len())
+            .map(|i| self.totals[i] - self.street_starts[i])
+            .max()
+            .unwrap_or(0);
+        let previous = self.totals[player] - self.street_starts[player];
+        let street_total = total - self.street_starts[player];
+        let mut line = match action {
+            Move::Fold => {
+                self.folded_on[player] = Some(self.street);
+                format!("{}: folds", name)
+            }
+            Move::Check => format!("{}: checks", name),
+            Move::Call => format!("{}: calls {}", name, amount),
+            Move::Bet if previous > 0 => format!("{}: completes it to {}", name, street_total),
+            Move::Bet => format!("{}: bets {}", name, street_total),
+            Move::Raise => format!(
+                "{}: raises {} to {}",
+                name,
+                street_total.saturating_sub(highest),
+                street_total
+            ),
+        };
+        if is_all_in {
+            line += " and is all-in";
+        }
+        self.lines.push(line);
+        self.totals[player] = total;
+    }
+
+    fn write_draw(&mut self, player: usize, discarded: &[Card], drawn: &[Card]) {
+        if self.acted_on_street.contains(&player) || self.acted_on_street.is_empty() {
+            self.start_street();
+            let ordinal = BOARD_NAMES.get(self.street - 1).unwrap_or(&"NEXT");
+            self.lines.push(format!("*** {} DRAW ***", ordinal));
+        }
+        self.acted_on_street.push(player);
+        let name = self.name(player);
+        if discarded.is_empty() {
+            self.lines.push(format!("{}: stands pat", name));
+            return;
+        }
+        let s = if discarded.len() == 1 { "" } else { "s" };
+        let is_visible = self.is_visible(player);
+        let mut line = format!("{}: discards {} card{}", name, discarded.len(), s);
+        if is_visible {
+            line += &format!(" [{}]", format_cards(discarded));
+        }
+        self.lines.push(line);
+        self.cards[player].retain(|c| !discarded.contains(c));
+        if is_visible {
+            self.lines.push(format!(
+                "Dealt to {} [{}] [{}]",
+                name,
+                format_cards(&self.cards[player]),
+                format_cards(drawn)
+            ));
+        }
+        self.cards[player].extend(drawn);
+    }
+
+    /// Writes the cards dealt to a board. Boards which are run out more than once are numbered.
+    fn write_board(&mut self, board: usize, cards: &[Card], is_run_out: bool) {
+        if board == 0 {
+            self.start_street();
+        }
+        if board >= self.boards.len() {
+            // Additional boards share the cards the first board had before this street
+            let shared = self.boards[0].len() - cards.len();
+            self.boards.push(self.boards[0][..shared].to_vec());
+        }
+        let known = self.boards[board].clone();
+        self.boards[board].extend(cards);
+        let prefix = if board > 0 || is_run_out {
+            format!("{} ", board_name(board))
+        } else {
+            String::new()
+        };
+        let street = match self.boards[board].len() {
+            3 => "FLOP",
+            4 => "TURN",
+            _ => "RIVER",
+        };
+        let line = if known.is_empty() {
+            format!("*** {}{} *** [{}]", prefix, street, format_cards(cards))
+        } else {
+            format!(
+                "*** {}{} *** [{}] [{}]",
+                prefix,
+                street,
+                format_cards(&known),
+                format_cards(cards)
+            )
+        };
+        self.lines.push(line);
+    }
+
+    fn write_summary(&mut self) {
+        self.lines.push("*** SUMMARY ***".to_string());
+        let pot: usize = self.won.iter().sum();
+        self.lines.push(format!("Total pot {} | Rake 0", pot));
+        let boards: Vec<&Vec<Card>> = self.boards.iter().filter(|b| !b.is_empty()).collect();
+        if boards.len() > 1 {
+            self.lines
+                .push(format!("Hand was run {} times", boards.len()));
+            for (i, board) in boards.iter().enumerate() {
+                self.lines
+                    .push(format!("{} Board [{}]", board_name(i), format_cards(board)));
+            }
+        } else if let Some(board) = boards.first() {
+            self.lines.push(format!("Board [{}]", format_cards(board)));
+        }
+        let Some(HandEvent::HandStarted {
+            dealer, players, ..
+        }) = self.history.events.first()
+        else {
+            return;
+        };
+        for player in players.iter().copied().sorted() {
+            let mut line = format!("Seat {}: {}", player + 1, self.name(player));
+            if player == *dealer && !self.variant.is_stud() {
+                line += " (button)";
+            }
+            match self.blinds[player] {
+                Some(ForcedBet::SmallBlind) => line += " (small blind)",
+                Some(ForcedBet::BigBlind) => line += " (big blind)",
+                _ => {}
+            }
+            let won = self.won[player];
+            line += &match (&self.shown[player], self.folded_on[player]) {
+                (_, Some(street)) => format!(" folded {}", self.street_name(street)),
+                (Some(cards), _) if won > 0 => {
+                    format!(" showed [{}] and won ({})", format_cards(cards), won)
+                }
+                (Some(cards), _) => format!(" showed [{}] and lost", format_cards(cards)),
+                (None, _) if won > 0 => format!(" collected ({})", won),
+                (None, _) => " mucked".to_string(),
+            };
+            self.lines.push(line);
+        }
+    }
+
+    /// Returns when a player folded on the street, as in the summary.
+    fn street_name(&self, street: usize) -> String {
+        if self.variant.is_stud() {
+            return match street + 3 {
+                7 => "on the River".to_string(),
+                s => format!("on the {}th Street", s),
+            };
+        }
+        if self.variant.draws() > 0 {
+            return match street {
+                0 => "before the Draw".to_string(),
+                s => format!("after the {} Draw", ordinal(s)),
+            };
+        }
+        match street {
+            0 => "before Flop",
+            1 => "on the Flop",
+            2 => "on the Turn",
+            _ => "on the River",
+        }
+        .to_string()
+    }
+}
+
+/// Returns the name of the variant in PokerStars hand histories.
+fn game_name(variant: GameVariant) -> &'static str {
+    match variant {
+        GameVariant::TexasHoldem => "Hold'em",
+        GameVariant::PotLimitOmaha => "Omaha",
+        GameVariant::OmahaHiLo => "Omaha Hi/Lo",
+        GameVariant::ShortDeck { .. } => "6+ Hold'em",
+        GameVariant::Pineapple => "Pineapple",
+        GameVariant::CrazyPineapple => "Crazy Pineapple",
+        GameVariant::Stud => "7 Card Stud",
+        GameVariant::StudHiLo => "7 Card Stud Hi/Lo",
+        GameVariant::Razz => "Razz",
+        GameVariant::DeuceToSevenTripleDraw => "Triple Draw 2-7 Lowball",
+    }
+}
+
+/// Returns the name of the board with the index, if the hand is run out more than once.
+fn board_name(board: usize) -> String {
+    match BOARD_NAMES.get(board) {
+        Some(name) => name.to_string(),
+        None => format!("BOARD {}", board + 1),
+    }
+}
+
+/// Returns the English ordinal of the number, such as 1st or 2nd.
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Writes the cards as PokerStars does, such as `Ah Td`.
+fn format_cards(cards: &[Card]) -> String {
+    const VALUES: &[u8; 13] = b"23456789TJQKA";
+    const SUITS: &[u8; 4] = b"cdhs";
+    cards
+        .iter()
+        .map(|card| {
+            let value = VALUES[card.value() as usize] as char;
+            let suit = SUITS[card.color() as usize] as char;
+            format!("{}{}", value, suit)
+        })
+        .join(" ")
+}
+
+/// Writes the seconds since the Unix epoch as UTC date and time, such as `2024/02/29 8:05:00`.
+fn format_time(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+    // Converts the days to the proleptic Gregorian calendar, in eras of 400 years
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{}/{:02}/{:02} {}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Utility function for parsing space separated cards.
+    fn cards(s: &str) -> Vec<Card> {
+        s.split(' ').map(|c| Card::try_from(c).unwrap()).collect()
+    }
+
+    /// Utility function for creating the history of a heads up hand, which is checked down
+    /// after a raise before the flop.
+    fn history() -> HandHistory {
+        HandHistory {
+            table: "Table 8080".to_string(),
+            hand_id: 1000,
+            started_at: 1_709_193_900,
+            player_names: vec!["Alice".to_string(), "".to_string()],
+            events: vec![
+                HandEvent::HandStarted {
+                    hand_number: 1,
+                    seed: Some(37),
+                    variant: GameVariant::TexasHoldem,
+                    betting: BettingStructure::NoLimit,
+                    dealer: 0,
+                    small_blind: 1,
+                    players: vec![1, 0],
+                    stacks: vec![100, 100],
+                },
+                HandEvent::CardsDealt {
+                    player: 1,
+                    cards: cards("DC D0"),
+                    up_cards: vec![],
+                },
+                HandEvent::CardsDealt {
+                    player: 0,
+                    cards: cards("AB BB"),
+                    up_cards: vec![],
+                },
+                HandEvent::BlindPosted {
+                    player: 1,
+                    kind: ForcedBet::SmallBlind,
+                    amount: 1,
+                },
+                HandEvent::BlindPosted {
+                    player: 0,
+                    kind: ForcedBet::BigBlind,
+                    amount: 2,
+                },
+                HandEvent::ActionTaken {
+                    player: 1,
+                    action: Move::Raise,
+                    amount: 5,
+                    total: 6,
+                    is_all_in: false,
+                },
+                HandEvent::ActionTaken {
+                    player: 0,
+                    action: Move::Call,
+                    amount: 4,
+                    total: 6,
+                    is_all_in: false,
+                },
+                HandEvent::BoardDealt {
+                    board: 0,
+                    cards: cards("A0 B5 C8"),
+                },
+                HandEvent::ActionTaken {
+                    player: 1,
+                    action: Move::Bet,
+                    amount: 10,
+                    total: 16,
+                    is_all_in: false,
+                },
+                HandEvent::ActionTaken {
+                    player: 0,
+                    action: Move::Call,
+                    amount: 10,
+                    total: 16,
+                    is_all_in: false,
+                },
+                HandEvent::BoardDealt {
+                    board: 0,
+                    cards: cards("D9"),
+                },
+                HandEvent::BoardDealt {
+                    board: 0,
+                    cards: cards("A3"),
+                },
+                HandEvent::HandShown {
+                    player: 1,
+                    cards: cards("DC D0"),
+                },
+                HandEvent::HandShown {
+                    player: 0,
+                    cards: cards("AB BB"),
+                },
+                HandEvent::PotAwarded {
+                    player: 0,
+                    amount: 32,
+                },
+                HandEvent::HandEnded {
+                    hand_number: 1,
+                    stacks: vec![116, 84],
+                },
+            ],
+        }
+    }
+
+    /// Testing a hand history as the tracking software of the first player sees it.
+    #[test]
+    fn test_pokerstars_hand() {
+        let expected = "\
+PokerStars Hand #1000:  Hold'em No Limit (1/2) - 2024/02/29 8:05:00 UTC
+Table 'Table 8080' 6-max Seat #1 is the button
+Seat 1: Alice (100 in chips)
+Seat 2: Player 2 (100 in chips)
+Player 2: posts small blind 1
+Alice: posts big blind 2
+*** HOLE CARDS ***
+Dealt to Alice [Kc Kd]
+Player 2: raises 4 to 6
+Alice: calls 4
+*** FLOP *** [2c 7d Th]
+Player 2: bets 10
+Alice: calls 10
+*** TURN *** [2c 7d Th] [Js]
+*** RIVER *** [2c 7d Th Js] [5c]
+*** SHOW DOWN ***
+Player 2: shows [As 2s]
+Alice: shows [Kc Kd]
+Alice collected 32 from pot
+*** SUMMARY ***
+Total pot 32 | Rake 0
+Board [2c 7d Th Js 5c]
+Seat 1: Alice (button) (big blind) showed [Kc Kd] and won (32)
+Seat 2: Player 2 (small blind) showed [As 2s] and lost
+";
+        assert_eq!(expected, history().to_pokerstars(Some(0)));
+
+        // Without a hero, everyone's hole cards are dealt face up
+        let all = history().to_pokerstars(None);
+        assert!(all.contains("Dealt to Alice [Kc Kd]\nDealt to Player 2 [As 2s]\n"));
+    }
+
+    /// Testing the streets of boards which are run out twice, stud games and draw games.
+    #[test]
+    fn test_pokerstars_streets() {
+        let mut run_twice = history();
+        run_twice.events.splice(
+            10..12,
+            vec![
+                HandEvent::BoardDealt {
+                    board: 0,
+                    cards: cards("D9"),
+                },
+                HandEvent::BoardDealt {
+                    board: 1,
+                    cards: cards("B9"),
+                },
+                HandEvent::BoardDealt {
+                    board: 0,
+                    cards: cards("A3"),
+                },
+                HandEvent::BoardDealt {
+                    board: 1,
+                    cards: cards("B3"),
+                },
+            ],
+        );
+        let mut stud = history();
+        stud.events[0] = HandEvent::HandStarted {
+            hand_number: 1,
+            seed: Some(37),
+            variant: GameVariant::Stud,
+            betting: BettingStructure::FixedLimit,
+            dealer: 0,
+            small_blind: 1,
+            players: vec![1, 0],
+            stacks: vec![100, 100],
+        };
+        stud.events.splice(
+            1..5,
+            vec![
+                HandEvent::CardsDealt {
+                    player: 1,
+                    cards: cards("DC D0 C4"),
+                    up_cards: cards("C4"),
+                },
+                HandEvent::CardsDealt {
+                    player: 0,
+                    cards: cards("AB BB C1"),
+                    up_cards: cards("C1"),
+                },
+                HandEvent::BlindPosted {
+                    player: 0,
+                    kind: ForcedBet::BringIn,
+                    amount: 1,
+                },
+            ],
+        );
+        stud.events.splice(
+            6..7,
+            vec![
+                HandEvent::CardsDealt {
+                    player: 1,
+                    cards: cards("A7"),
+                    up_cards: cards("A7"),
+                },
+                HandEvent::CardsDealt {
+                    player: 0,
+                    cards: cards("B7"),
+                    up_cards: cards("B7"),
+                },
+            ],
+        );
+        stud.events
+            .retain(|event| !matches!(event, HandEvent::BoardDealt { .. }));
+        let mut draw = history();
+        draw.events[0] = HandEvent::HandStarted {
+            hand_number: 1,
+            seed: Some(37),
+            variant: GameVariant::DeuceToSevenTripleDraw,
+            betting: BettingStructure::FixedLimit,
+            dealer: 0,
+            small_blind: 1,
+            players: vec![1, 0],
+            stacks: vec![100, 100],
+        };
+        draw.events.splice(
+            7..8,
+            vec![
+                HandEvent::CardsDrawn {
+                    player: 1,
+                    discarded: vec![],
+                    drawn: vec![],
+                },
+                HandEvent::CardsDrawn {
+                    player: 0,
+                    discarded: cards("AB"),
+                    drawn: cards("A4"),
+                },
+            ],
+        );
+        draw.events
+            .retain(|event| !matches!(event, HandEvent::BoardDealt { .. }));
+
+        let test_cases = vec![
+            (
+                run_twice,
+                vec![
+                    "*** FIRST TURN *** [2c 7d Th] [Js]",
+                    "*** SECOND TURN *** [2c 7d Th] [Jd]",
+                    "*** FIRST RIVER *** [2c 7d Th Js] [5c]",
+                    "*** SECOND RIVER *** [2c 7d Th Jd] [5d]",
+                    "Hand was run 2 times\nFIRST Board [2c 7d Th Js 5c]\nSECOND Board [2c 7d Th Jd 5d]",
+                ],
+            ),
+            (
+                stud,
+                vec![
+                    "PokerStars Hand #1000:  7 Card Stud Limit (2/4)",
+                    "Table 'Table 8080' 6-max\n",
+                    "*** 3rd STREET ***\nDealt to Alice [Kc Kd 3h]\nDealt to Player 2 [6h]\nAlice: brings in for 1\n",
+                    "*** 4th STREET ***\nDealt to Player 2 [6h] [9c]\nDealt to Alice [Kc Kd 3h] [9d]",
+                    "Seat 1: Alice showed",
+                ],
+            ),
+            (
+                draw,
+                vec![
+                    "*** DEALING HANDS ***\nDealt to Alice [Kc Kd]",
+                    "*** FIRST DRAW ***\nPlayer 2: stands pat\nAlice: discards 1 card [Kc]\nDealt to Alice [Kd] [6c]",
+                    "Alice: calls 4\n*** FIRST DRAW ***",
+                ],
+            ),
+        ];
+        for (history, expected_parts) in test_cases {
+            let text = history.to_pokerstars(Some(0));
+            for part in expected_parts {
+                assert!(
+                    text.contains(part),
+                    "Failed: {:?}. Expected to contain: {}. Got:\n{}",
+                    history.events[0],
+                    part,
+                    text
+                );
+            }
+        }
+    }
+
+    /// Utility function for playing a hand on the engine, where every player calls and the first one raises.
+    fn played_history(variant: GameVariant) -> HandHistory {
+        use crate::betting::StraddleRule;
+        use crate::engine::{Action, Engine, Event, TableSettings};
+        use crate::raw_message::PlayerChoice;
+        use crate::rotation::Rotation;
+
+        let settings = TableSettings {
+            variant,
+            betting: variant.default_betting_structure(),
+            rotation: Rotation::Fixed,
+            straddles: StraddleRule::Off,
+            small_blind: 1,
+            start_money: 100,
+            max_run_outs: 2,
+            rabbit_hunt_interval: None,
+            bomb_pot: None,
+            is_shuffle_audited: false,
+        };
+        let mut engine = Engine::new(settings, 3, 11);
+        let mut events = vec![];
+        let mut new_events = engine.start();
+        let mut is_raised = false;
+        while !events
+            .iter()
+            .any(|event| matches!(event, HandEvent::HandEnded { .. }))
+        {
+            let mut action = Action::Timeout;
+            for event in new_events {
+                match event {
+                    Event::Logged(event) => events.push(event),
+                    Event::AwaitingBet { player, .. } => {
+                        let choice = match is_raised {
+                            true => PlayerChoice::Pass,
+                            false => PlayerChoice::RaiseTo(8),
+                        };
+                        is_raised = true;
+                        action = Action::Choice { player, choice }
+                    }
+                    _ => {}
+                }
+            }
+            new_events = engine.apply(action);
+        }
+        HandHistory {
+            table: "Table \"8080\"".to_string(),
+            hand_id: 7,
+            started_at: 1_700_000_000,
+            player_names: vec!["Änne".to_string(), "".to_string(), "Bob".to_string()],
+            events,
+        }
+    }
+
+    /// Testing that hand histories are read back from JSON exactly as they were written.
+    #[test]
+    fn test_json_round_trip() {
+        let mut short_deck = history();
+        short_deck.events[0] = HandEvent::HandStarted {
+            hand_number: 1,
+            seed: Some(37),
+            variant: GameVariant::ShortDeck {
+                trips_beat_straight: true,
+            },
+            betting: BettingStructure::NoLimit,
+            dealer: 0,
+            small_blind: 1,
+            players: vec![1, 0],
+            stacks: vec![100, 100],
+        };
+        let histories = vec![
+            history(),
+            short_deck,
+            played_history(GameVariant::TexasHoldem),
+            played_history(GameVariant::Stud),
+            played_history(GameVariant::Pineapple),
+            played_history(GameVariant::DeuceToSevenTripleDraw),
+        ];
+        let json = histories.iter().map(HandHistory::to_json).join("\n");
+        let read = HandHistory::from_json_lines(&json).unwrap();
+        assert_eq!(histories, read);
+        assert_eq!(json, read.iter().map(HandHistory::to_json).join("\n"));
+
+        let invalid_cases = vec![
+            r#"{"table": "T", "hand_id": 1, "started_at": 0, "player_names": []}"#,
+            r#"{"table": "T", "hand_id": -1, "started_at": 0, "player_names": [], "events": []}"#,
+            r#"{"table": "T", "hand_id": 1, "started_at": 0, "player_names": [], "events": [{"type": "hand_lost"}]}"#,
+            r#"{"table": "T", "hand_id": 1, "started_at": 0, "player_names": [1], "events": []}"#,
+            "{",
+        ];
+        for json in invalid_cases {
+            assert!(
+                HandHistory::from_json_lines(json).is_err(),
+                "Failed: {}. Expected an error",
+                json
+            );
+        }
+    }
+
+    /// Testing that the written JSON has exactly the fields the schema requires.
+    #[test]
+    fn test_json_schema() {
+        let schema: Value = serde_json::from_str(JSON_SCHEMA).unwrap();
+        let keys = |value: &Value| -> Vec<String> {
+            value
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .sorted()
+                .collect()
+        };
+        let strings = |value: &Value| -> Vec<String> {
+            let values = value.as_array().unwrap();
+            values
+                .iter()
+                .map(|s| s.as_str().unwrap().to_string())
+                .sorted()
+                .collect()
+        };
+        let check = |value: &Value, schema: &Value| {
+            let properties = keys(&schema["properties"]);
+            let required = strings(&schema["required"]);
+            let keys = keys(value);
+            assert!(
+                keys.iter().all(|key| properties.contains(key))
+                    && required.iter().all(|key| keys.contains(key)),
+                "Failed: {}. Expected the fields of {}",
+                value,
+                schema
+            );
+        };
+        let branches = schema["$defs"]["event"]["oneOf"].as_array().unwrap();
+        let mut types: Vec<&str> = vec![];
+        for history in [history(), played_history(GameVariant::Pineapple)] {
+            let value = Value::from(history);
+            check(&value, &schema);
+            for event in value["events"].as_array().unwrap() {
+                let branch = branches
+                    .iter()
+                    .find(|branch| branch["properties"]["type"]["const"] == event["type"])
+                    .unwrap_or_else(|| panic!("Failed: {}. Expected in the schema", event));
+                check(event, branch);
+                types.push(branch["properties"]["type"]["const"].as_str().unwrap());
+            }
+        }
+        // Not every event happens in these hands
+        assert!(types.iter().unique().count() >= 8);
+    }
+
+    /// Testing the conversion of Unix times to UTC dates.
+    #[test]
+    fn test_format_time() {
+        let test_cases = vec![
+            (0, "1970/01/01 0:00:00"),
+            (951_782_400, "2000/02/29 0:00:00"),
+            (1_709_193_900, "2024/02/29 8:05:00"),
+            (1_798_761_599, "2026/12/31 23:59:59"),
+        ];
+        for (seconds, expected) in test_cases {
+            assert_eq!(
+                expected,
+                format_time(seconds),
+                "Failed: {}. Expected: {}",
+                seconds,
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,1019 @@
+use crate::betting::{BettingStructure, StraddleRule};
+use crate::cards::Card;
+use crate::engine::{Action, BombPotSettings, SeatState, TableSettings, TableState};
+use crate::raw_message::RawMessage;
+use crate::rotation::Rotation;
+use crate::variant::GameVariant;
+use serde_json::{json, Value};
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+/// A forced bet, which is posted before the player could act.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForcedBet {
+    SmallBlind,
+    BigBlind,
+    Straddle,
+    BringIn,
+    Ante,
+}
+
+/// What a player did when it was his turn to bet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    Fold,
+    Check,
+    Call,
+    Bet,
+    Raise,
+}
+
+/// Everything that happened in a hand, in the order it happened.
+/// Players are identified by their seat. Amounts of bets are the total bet of the player in the hand,
+/// as the engine collects the pot only at the end of the hand.
+///
+/// The log also records how the table was opened and closed and every action applied to the engine,
+/// from which the engine plays the same hands once more.
+///
+/// Events are logged as JSON objects:
+///
+/// ```
+/// use nolimittexasholdem::hand_log::{HandEvent, Move};
+/// use serde_json::{json, Value};
+///
+/// let event = HandEvent::ActionTaken { player: 1, action: Move::Raise, amount: 6, total: 8, is_all_in: false };
+/// let value = Value::from(event.clone());
+/// assert_eq!(
+///     json!({"type": "action_taken", "player": 1, "action": "raise", "amount": 6, "total": 8, "is_all_in": false}),
+///     value
+/// );
+/// assert_eq!(event, HandEvent::try_from(value).unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum HandEvent {
+    /// The table was opened with the settings, the seed of its engine and the amount of players.
+    TableOpened {
+        seed: u64,
+        players: usize,
+        settings: TableSettings,
+    },
+
+    /// The table was restored from its state between two hands, after the server was restarted.
+    /// The absent players did not return to the table.
+    TableRestored {
+        state: TableState,
+        absent: Vec<usize>,
+    },
+
+    /// The action was applied to the engine, which may have ignored it.
+    ActionApplied { action: Action },
+
+    /// The table was closed when the server shut down, either between two hands or by refunding the hand.
+    TableClosed { refunded_hand: Option<usize> },
+
+    /// A hand was shuffled. The players are in the order they were dealt, the stacks are those of all seats.
+    /// The seed is the one the hand was shuffled with, see `hand_seed`. Audited shuffles have none,
+    /// they are seeded by the seeds revealed after the hand.
+    HandStarted {
+        hand_number: usize,
+        seed: Option<u64>,
+        variant: GameVariant,
+        betting: BettingStructure,
+        dealer: usize,
+        small_blind: usize,
+        players: Vec<usize>,
+        stacks: Vec<usize>,
+    },
+
+    /// A player posted a forced bet, which may be less than asked if the player is all in.
+    BlindPosted {
+        player: usize,
+        kind: ForcedBet,
+        amount: usize,
+    },
+
+    /// A player was dealt cards, of which the up cards are face up.
+    CardsDealt {
+        player: usize,
+        cards: Vec<Card>,
+        up_cards: Vec<Card>,
+    },
+
+    /// A player acted and added the amount to his bet, which is the total afterwards.
+    ActionTaken {
+        player: usize,
+        action: Move,
+        amount: usize,
+        total: usize,
+        is_all_in: bool,
+    },
+
+    /// A player discarded a hole card, as in pineapple.
+    CardDiscarded { player: usize, card: Card },
+
+    /// A player exchanged cards, as in draw games. Players who stand pat draw no cards.
+    CardsDrawn {
+        player: usize,
+        discarded: Vec<Card>,
+        drawn: Vec<Card>,
+    },
+
+    /// Cards were dealt to the board with the index, the first board are the open middle cards.
+    /// Additional boards of a run-out share the cards the first board had when they were added.
+    BoardDealt { board: usize, cards: Vec<Card> },
+
+    /// A player showed his cards at the showdown.
+    HandShown { player: usize, cards: Vec<Card> },
+
+    /// The part of a bet nobody called was returned to the player.
+    BetReturned { player: usize, amount: usize },
+
+    /// A player won the amount from the pot.
+    PotAwarded { player: usize, amount: usize },
+
+    /// The hand ended with the stacks of all seats.
+    HandEnded {
+        hand_number: usize,
+        stacks: Vec<usize>,
+    },
+
+    /// The server seed of the audited shuffle of the hand and the seeds of the players were revealed.
+    /// A replay shuffles the hand with them, as the secret of the table is never logged.
+    ShuffleRevealed {
+        hand_number: usize,
+        server_seed: String,
+        client_seeds: Vec<Option<String>>,
+    },
+}
+
+impl TryFrom<&str> for ForcedBet {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "small_blind" => Ok(Self::SmallBlind),
+            "big_blind" => Ok(Self::BigBlind),
+            "straddle" => Ok(Self::Straddle),
+            "bring_in" => Ok(Self::BringIn),
+            "ante" => Ok(Self::Ante),
+            v => Err(format!("No forced bet: {}", v)),
+        }
+    }
+}
+
+impl fmt::Display for ForcedBet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::SmallBlind => "small_blind",
+            Self::BigBlind => "big_blind",
+            Self::Straddle => "straddle",
+            Self::BringIn => "bring_in",
+            Self::Ante => "ante",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl TryFrom<&str> for Move {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "fold" => Ok(Self::Fold),
+            "check" => Ok(Self::Check),
+            "call" => Ok(Self::Call),
+            "bet" => Ok(Self::Bet),
+            "raise" => Ok(Self::Raise),
+            v => Err(format!("No move: {}", v)),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Fold => "fold",
+            Self::Check => "check",
+            Self::Call => "call",
+            Self::Bet => "bet",
+            Self::Raise => "raise",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl From<HandEvent> for Value {
+    fn from(event: HandEvent) -> Self {
+        let values = |cards: Vec<Card>| -> Vec<u8> { cards.into_iter().map(|c| c.value).collect() };
+        match event {
+            HandEvent::TableOpened {
+                seed,
+                players,
+                settings,
+            } => json!({
+                "type": "table_opened",
+                "seed": seed,
+                "players": players,
+                "settings": settings_value(settings)
+            }),
+            HandEvent::TableRestored { state, absent } => json!({
+                "type": "table_restored",
+                "state": Value::from(state),
+                "absent": absent
+            }),
+            HandEvent::ActionApplied { action } => match action.to_message() {
+                Some((player, message)) => json!({
+                    "type": "action_applied",
+                    "player": player,
+                    "message": Value::from(message)
+                }),
+                // Timeouts come from no player
+                None => json!({"type": "action_applied"}),
+            },
+            HandEvent::TableClosed { refunded_hand } => json!({
+                "type": "table_closed",
+                "refunded_hand": refunded_hand
+            }),
+            HandEvent::HandStarted {
+                hand_number,
+                seed,
+                variant,
+                betting,
+                dealer,
+                small_blind,
+                players,
+                stacks,
+            } => {
+                let mut value = json!({
+                    "type": "hand_started",
+                    "hand_number": hand_number,
+                    "seed": seed,
+                    "variant": variant.to_string(),
+                    "betting": betting.to_string(),
+                    "dealer": dealer,
+                    "small_blind": small_blind,
+                    "players": players,
+                    "stacks": stacks
+                });
+                // The name of the variant does not tell the rules of short deck
+                if let GameVariant::ShortDeck {
+                    trips_beat_straight,
+                } = variant
+                {
+                    value["trips_beat_straight"] = json!(trips_beat_straight);
+                }
+                value
+            }
+            HandEvent::BlindPosted {
+                player,
+                kind,
+                amount,
+            } => json!({
+                "type": "blind_posted",
+                "player": player,
+                "kind": kind.to_string(),
+                "amount": amount
+            }),
+            HandEvent::CardsDealt {
+                player,
+                cards,
+                up_cards,
+            } => json!({
+                "type": "cards_dealt",
+                "player": player,
+                "cards": values(cards),
+                "up_cards": values(up_cards)
+            }),
+            HandEvent::ActionTaken {
+                player,
+                action,
+                amount,
+                total,
+                is_all_in,
+            } => json!({
+                "type": "action_taken",
+                "player": player,
+                "action": action.to_string(),
+                "amount": amount,
+                "total": total,
+                "is_all_in": is_all_in
+            }),
+            HandEvent::CardDiscarded { player, card } => json!({
+                "type": "card_discarded",
+                "player": player,
+                "card": card.value
+            }),
+            HandEvent::CardsDrawn {
+                player,
+                discarded,
+                drawn,
+            } => json!({
+                "type": "cards_drawn",
+                "player": player,
+                "discarded": values(discarded),
+                "drawn": values(drawn)
+            }),
+            HandEvent::BoardDealt { board, cards } => json!({
+                "type": "board_dealt",
+                "board": board,
+                "cards": values(cards)
+            }),
+            HandEvent::HandShown { player, cards } => json!({
+                "type": "hand_shown",
+                "player": player,
+                "cards": values(cards)
+            }),
+            HandEvent::BetReturned { player, amount } => json!({
+                "type": "bet_returned",
+                "player": player,
+                "amount": amount
+            }),
+            HandEvent::PotAwarded { player, amount } => json!({
+                "type": "pot_awarded",
+                "player": player,
+                "amount": amount
+            }),
+            HandEvent::HandEnded {
+                hand_number,
+                stacks,
+            } => json!({
+                "type": "hand_ended",
+                "hand_number": hand_number,
+                "stacks": stacks
+            }),
+            HandEvent::ShuffleRevealed {
+                hand_number,
+                server_seed,
+                client_seeds,
+            } => json!({
+                "type": "shuffle_revealed",
+                "hand_number": hand_number,
+                "server_seed": server_seed,
+                "client_seeds": client_seeds
+            }),
+        }
+    }
+}
+
+impl TryFrom<Value> for HandEvent {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match string(&value, "type")? {
+            "table_opened" => Ok(Self::TableOpened {
+                seed: value["seed"].as_u64().ok_or("Missing field: seed")?,
+                players: number(&value, "players")?,
+                settings: settings(&value["settings"])?,
+            }),
+            "table_restored" => Ok(Self::TableRestored {
+                state: TableState::try_from(value["state"].clone())?,
+                absent: numbers(&value, "absent")?,
+            }),
+            "action_applied" => {
+                let action = match value.get("message") {
+                    Some(message) => {
+                        let player = number(&value, "player")?;
+                        let message =
+                            RawMessage::try_from(message.clone()).map_err(|e| e.to_string())?;
+                        Action::from_message(player, message)
+                            .ok_or_else(|| format!("No action: {}", value["message"]))?
+                    }
+                    None => Action::Timeout,
+                };
+                Ok(Self::ActionApplied { action })
+            }
+            "table_closed" => Ok(Self::TableClosed {
+                refunded_hand: optional_number(&value, "refunded_hand")?,
+            }),
+            "hand_started" => {
+                let mut variant = GameVariant::try_from(string(&value, "variant")?)?;
+                if let GameVariant::ShortDeck {
+                    trips_beat_straight,
+                } = &mut variant
+                {
+                    *trips_beat_straight = value["trips_beat_straight"].as_bool().unwrap_or(false);
+                }
+                let seed = match &value["seed"] {
+                    Value::Null => None,
+                    seed => Some(seed.as_u64().ok_or(format!("No seed: {}", seed))?),
+                };
+                Ok(Self::HandStarted {
+                    hand_number: number(&value, "hand_number")?,
+                    seed,
+                    variant,
+                    betting: BettingStructure::try_from(string(&value, "betting")?)?,
+                    dealer: number(&value, "dealer")?,
+                    small_blind: number(&value, "small_blind")?,
+                    players: numbers(&value, "players")?,
+                    stacks: numbers(&value, "stacks")?,
+                })
+            }
+            "blind_posted" => Ok(Self::BlindPosted {
+                player: number(&value, "player")?,
+                kind: ForcedBet::try_from(string(&value, "kind")?)?,
+                amount: number(&value, "amount")?,
+            }),
+            "cards_dealt" => Ok(Self::CardsDealt {
+                player: number(&value, "player")?,
+                cards: cards(&value, "cards")?,
+                up_cards: cards(&value, "up_cards")?,
+            }),
+            "action_taken" => Ok(Self::ActionTaken {
+                player: number(&value, "player")?,
+                action: Move::try_from(string(&value, "action")?)?,
+                amount: number(&value, "amount")?,
+                total: number(&value, "total")?,
+                is_all_in: value["is_all_in"]
+                    .as_bool()
+                    .ok_or("Missing field: is_all_in")?,
+            }),
+            "card_discarded" => Ok(Self::CardDiscarded {
+                player: number(&value, "player")?,
+                card: card(&value["card"])?,
+            }),
+            "cards_drawn" => Ok(Self::CardsDrawn {
+                player: number(&value, "player")?,
+                discarded: cards(&value, "discarded")?,
+                drawn: cards(&value, "drawn")?,
+            }),
+            "board_dealt" => Ok(Self::BoardDealt {
+                board: number(&value, "board")?,
+                cards: cards(&value, "cards")?,
+            }),
+            "hand_shown" => Ok(Self::HandShown {
+                player: number(&value, "player")?,
+                cards: cards(&value, "cards")?,
+            }),
+            "bet_returned" => Ok(Self::BetReturned {
+                player: number(&value, "player")?,
+                amount: number(&value, "amount")?,
+            }),
+            "pot_awarded" => Ok(Self::PotAwarded {
+                player: number(&value, "player")?,
+                amount: number(&value, "amount")?,
+            }),
+            "hand_ended" => Ok(Self::HandEnded {
+                hand_number: number(&value, "hand_number")?,
+                stacks: numbers(&value, "stacks")?,
+            }),
+            "shuffle_revealed" => Ok(Self::ShuffleRevealed {
+                hand_number: number(&value, "hand_number")?,
+                server_seed: string(&value, "server_seed")?.to_string(),
+                client_seeds: optional_strings(&value, "client_seeds")?,
+            }),
+            t => Err(format!("No hand event: {}", t)),
+        }
+    }
+}
+
+impl From<TableState> for Value {
+    fn from(state: TableState) -> Self {
+        let seats: Vec<Value> = state
+            .seats
+            .into_iter()
+            .map(|seat| {
+                json!({
+                    "coins": seat.coins,
+                    "is_out": seat.is_out,
+                    "last_rabbit_hunt": seat.last_rabbit_hunt
+                })
+            })
+            .collect();
+        json!({
+            "settings": settings_value(state.settings),
+            "seed": state.seed,
+            "hand_number": state.hand_number,
+            "hands_with_variant": state.hands_with_variant,
+            "dealer": state.dealer,
+            "small_blind": state.small_blind,
+            "seats": seats
+        })
+    }
+}
+
+impl TryFrom<Value> for TableState {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let seats = value["seats"]
+            .as_array()
+            .ok_or("Missing field: seats")?
+            .iter()
+            .map(|seat| {
+                Ok(SeatState {
+                    coins: number(seat, "coins")?,
+                    is_out: seat["is_out"].as_bool().ok_or("Missing field: is_out")?,
+                    last_rabbit_hunt: optional_number(seat, "last_rabbit_hunt")?,
+                })
+            })
+            .collect::<Result<Vec<SeatState>, String>>()?;
+        Ok(Self {
+            settings: settings(&value["settings"])?,
+            seed: value["seed"].as_u64().ok_or("Missing field: seed")?,
+            hand_number: number(&value, "hand_number")?,
+            hands_with_variant: number(&value, "hands_with_variant")?,
+            dealer: number(&value, "dealer")?,
+            small_blind: number(&value, "small_blind")?,
+            seats,
+        })
+    }
+}
+
+/// Converts the settings of a table to JSON. Fields which are not set are null.
+fn settings_value(settings: TableSettings) -> Value {
+    let mut value = json!({
+        "variant": settings.variant.to_string(),
+        "betting": settings.betting.to_string(),
+        "rotation": settings.rotation.to_string(),
+        "straddles": settings.straddles.to_string(),
+        "small_blind": settings.small_blind,
+        "start_money": settings.start_money,
+        "max_run_outs": settings.max_run_outs,
+        "rabbit_hunt_interval": settings.rabbit_hunt_interval,
+        "bomb_pot": settings.bomb_pot.map(|bomb_pot| json!({
+            "ante": bomb_pot.ante,
+            "interval": bomb_pot.interval,
+            "is_voted": bomb_pot.is_voted,
+            "is_double_board": bomb_pot.is_double_board
+        })),
+        "is_shuffle_audited": settings.is_shuffle_audited
+    });
+    if let GameVariant::ShortDeck {
+        trips_beat_straight,
+    } = settings.variant
+    {
+        value["trips_beat_straight"] = json!(trips_beat_straight);
+    }
+    if let Rotation::Horse { hands_per_variant } = settings.rotation {
+        value["hands_per_variant"] = json!(hands_per_variant);
+    }
+    value
+}
+
+/// Reads the settings of a table, as converted by `settings_value`.
+fn settings(value: &Value) -> Result<TableSettings, String> {
+    let flag = |value: &Value, key: &str| {
+        value[key]
+            .as_bool()
+            .ok_or_else(|| format!("Missing field: {}", key))
+    };
+    let mut variant = GameVariant::try_from(string(value, "variant")?)?;
+    if let GameVariant::ShortDeck {
+        trips_beat_straight,
+    } = &mut variant
+    {
+        *trips_beat_straight = flag(value, "trips_beat_straight")?;
+    }
+    let mut rotation = Rotation::try_from(string(value, "rotation")?)?;
+    if let Rotation::Horse { hands_per_variant } = &mut rotation {
+        *hands_per_variant = number(value, "hands_per_variant")?;
+    }
+    let bomb_pot = match &value["bomb_pot"] {
+        Value::Null => None,
+        bomb_pot => Some(BombPotSettings {
+            ante: number(bomb_pot, "ante")?,
+            interval: optional_number(bomb_pot, "interval")?,
+            is_voted: flag(bomb_pot, "is_voted")?,
+            is_double_board: flag(bomb_pot, "is_double_board")?,
+        }),
+    };
+    Ok(TableSettings {
+        variant,
+        betting: BettingStructure::try_from(string(value, "betting")?)?,
+        rotation,
+        straddles: StraddleRule::try_from(string(value, "straddles")?)?,
+        small_blind: number(value, "small_blind")?,
+        start_money: number(value, "start_money")?,
+        max_run_outs: number(value, "max_run_outs")?,
+        rabbit_hunt_interval: optional_number(value, "rabbit_hunt_interval")?,
+        bomb_pot,
+        is_shuffle_audited: flag(value, "is_shuffle_audited")?,
+    })
+}
+
+/// Reads the string field of a logged event.
+fn string<'a>(value: &'a Value, key: &str) -> Result<&'a str, String> {
+    value[key]
+        .as_str()
+        .ok_or_else(|| format!("Missing field: {}", key))
+}
+
+/// Reads the number field of a logged event.
+fn number(value: &Value, key: &str) -> Result<usize, String> {
+    value[key]
+        .as_u64()
+        .map(|n| n as usize)
+        .ok_or_else(|| format!("Missing field: {}", key))
+}
+
+/// Reads the number field of a logged event which may be null.
+fn optional_number(value: &Value, key: &str) -> Result<Option<usize>, String> {
+    match &value[key] {
+        Value::Null => Ok(None),
+        n => n
+            .as_u64()
+            .map(|n| Some(n as usize))
+            .ok_or(format!("No number: {}", n)),
+    }
+}
+
+/// Reads the field of a logged event which is a list of numbers.
+fn numbers(value: &Value, key: &str) -> Result<Vec<usize>, String> {
+    value[key]
+        .as_array()
+        .ok_or_else(|| format!("Missing field: {}", key))?
+        .iter()
+        .map(|n| {
+            n.as_u64()
+                .map(|n| n as usize)
+                .ok_or(format!("No number: {}", n))
+        })
+        .collect()
+}
+
+/// Reads the field of a logged event which is a list of strings, any of which may be null.
+fn optional_strings(value: &Value, key: &str) -> Result<Vec<Option<String>>, String> {
+    value[key]
+        .as_array()
+        .ok_or_else(|| format!("Missing field: {}", key))?
+        .iter()
+        .map(|s| match s {
+            Value::Null => Ok(None),
+            s => s
+                .as_str()
+                .map(|s| Some(s.to_string()))
+                .ok_or(format!("No string: {}", s)),
+        })
+        .collect()
+}
+
+/// Reads a card, which is logged as its value.
+fn card(value: &Value) -> Result<Card, String> {
+    match value.as_u64() {
+        Some(v) if v < 52 => Ok(Card { value: v as u8 }),
+        _ => Err(format!("No card: {}", value)),
+    }
+}
+
+/// Reads the field of a logged event which is a list of cards.
+fn cards(value: &Value, key: &str) -> Result<Vec<Card>, String> {
+    value[key]
+        .as_array()
+        .ok_or_else(|| format!("Missing field: {}", key))?
+        .iter()
+        .map(card)
+        .collect()
+}
+
+/// An append-only log of the events of every hand played at a table, one JSON object per line.
+pub struct HandLog {
+    file: File,
+}
+
+impl HandLog {
+    /// Opens the log at the path, which is created if it does not exist yet.
+    /// Events of earlier games are kept, new events are appended.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    /// Appends the event to the log. Every event is written at once, so the log never ends in half an event.
+    pub fn append(&mut self, event: HandEvent) -> io::Result<()> {
+        let line = Value::from(event).to_string() + "\n";
+        self.file.write_all(line.as_bytes())
+    }
+
+    /// Reads all events of the log at the path, in the order they were appended.
+    pub fn read(path: impl AsRef<Path>) -> Result<Vec<HandEvent>, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        BufReader::new(file)
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+            .map(|(i, line)| {
+                let line = line.map_err(|e| e.to_string())?;
+                let value: Value = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+                HandEvent::try_from(value).map_err(|e| format!("Line {}: {}", i + 1, e))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw_message::PlayerChoice;
+
+    /// Testing that every event is logged and read back unchanged.
+    #[test]
+    fn test_hand_event_conversion() {
+        let c = |s: &str| Card::try_from(s).unwrap();
+        let test_cases = vec![
+            (
+                HandEvent::TableOpened {
+                    seed: u64::MAX,
+                    players: 3,
+                    settings: TableSettings {
+                        variant: GameVariant::Razz,
+                        betting: BettingStructure::FixedLimit,
+                        rotation: Rotation::Horse {
+                            hands_per_variant: 4,
+                        },
+                        straddles: StraddleRule::Restraddle,
+                        small_blind: 5,
+                        start_money: 500,
+                        max_run_outs: 2,
+                        rabbit_hunt_interval: Some(3),
+                        bomb_pot: Some(BombPotSettings {
+                            ante: 10,
+                            interval: None,
+                            is_voted: true,
+                            is_double_board: false,
+                        }),
+                        is_shuffle_audited: true,
+                    },
+                },
+                r#"{"type": "table_opened", "seed": 18446744073709551615, "players": 3, "settings": {"variant": "razz", "betting": "fl", "rotation": "horse", "hands_per_variant": 4, "straddles": "restraddle", "small_blind": 5, "start_money": 500, "max_run_outs": 2, "rabbit_hunt_interval": 3, "bomb_pot": {"ante": 10, "interval": null, "is_voted": true, "is_double_board": false}, "is_shuffle_audited": true}}"#,
+            ),
+            (
+                HandEvent::TableOpened {
+                    seed: 7,
+                    players: 2,
+                    settings: TableSettings {
+                        variant: GameVariant::ShortDeck {
+                            trips_beat_straight: true,
+                        },
+                        betting: BettingStructure::NoLimit,
+                        rotation: Rotation::Fixed,
+                        straddles: StraddleRule::Off,
+                        small_blind: 1,
+                        start_money: 100,
+                        max_run_outs: 1,
+                        rabbit_hunt_interval: None,
+                        bomb_pot: None,
+                        is_shuffle_audited: false,
+                    },
+                },
+                r#"{"type": "table_opened", "seed": 7, "players": 2, "settings": {"variant": "shortdeck", "trips_beat_straight": true, "betting": "nl", "rotation": "none", "straddles": "off", "small_blind": 1, "start_money": 100, "max_run_outs": 1, "rabbit_hunt_interval": null, "bomb_pot": null, "is_shuffle_audited": false}}"#,
+            ),
+            (
+                HandEvent::TableRestored {
+                    state: TableState {
+                        settings: TableSettings {
+                            variant: GameVariant::Stud,
+                            betting: BettingStructure::FixedLimit,
+                            rotation: Rotation::DealersChoice,
+                            straddles: StraddleRule::Off,
+                            small_blind: 1,
+                            start_money: 100,
+                            max_run_outs: 1,
+                            rabbit_hunt_interval: Some(2),
+                            bomb_pot: None,
+                            is_shuffle_audited: false,
+                        },
+                        seed: 3,
+                        hand_number: 12,
+                        hands_with_variant: 1,
+                        dealer: 1,
+                        small_blind: 4,
+                        seats: vec![
+                            SeatState {
+                                coins: 0,
+                                is_out: true,
+                                last_rabbit_hunt: None,
+                            },
+                            SeatState {
+                                coins: 200,
+                                is_out: false,
+                                last_rabbit_hunt: Some(11),
+                            },
+                        ],
+                    },
+                    absent: vec![0],
+                },
+                r#"{"type": "table_restored", "absent": [0], "state": {"settings": {"variant": "stud", "betting": "fl", "rotation": "dealerschoice", "straddles": "off", "small_blind": 1, "start_money": 100, "max_run_outs": 1, "rabbit_hunt_interval": 2, "bomb_pot": null, "is_shuffle_audited": false}, "seed": 3, "hand_number": 12, "hands_with_variant": 1, "dealer": 1, "small_blind": 4, "seats": [{"coins": 0, "is_out": true, "last_rabbit_hunt": null}, {"coins": 200, "is_out": false, "last_rabbit_hunt": 11}]}}"#,
+            ),
+            (
+                HandEvent::ActionApplied {
+                    action: Action::Choice {
+                        player: 1,
+                        choice: PlayerChoice::RaiseTo(40),
+                    },
+                },
+                r#"{"type": "action_applied", "player": 1, "message": {"type": "response", "action": "raise_to", "amount": 40}}"#,
+            ),
+            (
+                HandEvent::ActionApplied {
+                    action: Action::Choice {
+                        player: 0,
+                        choice: PlayerChoice::Draw(vec![c("A0"), c("BC")]),
+                    },
+                },
+                r#"{"type": "action_applied", "player": 0, "message": {"type": "response", "action": "draw", "cards": [0, 25]}}"#,
+            ),
+            (
+                HandEvent::ActionApplied {
+                    action: Action::ClientSeed {
+                        player: 2,
+                        seed: "lucky".to_string(),
+                    },
+                },
+                r#"{"type": "action_applied", "player": 2, "message": {"type": "client_seed", "seed": "lucky"}}"#,
+            ),
+            (
+                HandEvent::ActionApplied {
+                    action: Action::Timeout,
+                },
+                r#"{"type": "action_applied"}"#,
+            ),
+            (
+                HandEvent::TableClosed {
+                    refunded_hand: Some(4),
+                },
+                r#"{"type": "table_closed", "refunded_hand": 4}"#,
+            ),
+            (
+                HandEvent::TableClosed {
+                    refunded_hand: None,
+                },
+                r#"{"type": "table_closed", "refunded_hand": null}"#,
+            ),
+            (
+                HandEvent::HandStarted {
+                    hand_number: 3,
+                    seed: Some(u64::MAX),
+                    variant: GameVariant::OmahaHiLo,
+                    betting: BettingStructure::PotLimit,
+                    dealer: 2,
+                    small_blind: 5,
+                    players: vec![0, 1, 2],
+                    stacks: vec![100, 0, 250, 80],
+                },
+                r#"{"type": "hand_started", "hand_number": 3, "seed": 18446744073709551615, "variant": "plo8", "betting": "pl", "dealer": 2, "small_blind": 5, "players": [0, 1, 2], "stacks": [100, 0, 250, 80]}"#,
+            ),
+            (
+                HandEvent::HandStarted {
+                    hand_number: 4,
+                    seed: None,
+                    variant: GameVariant::ShortDeck {
+                        trips_beat_straight: true,
+                    },
+                    betting: BettingStructure::NoLimit,
+                    dealer: 0,
+                    small_blind: 5,
+                    players: vec![1, 0],
+                    stacks: vec![100, 100],
+                },
+                r#"{"type": "hand_started", "hand_number": 4, "seed": null, "variant": "shortdeck", "trips_beat_straight": true, "betting": "nl", "dealer": 0, "small_blind": 5, "players": [1, 0], "stacks": [100, 100]}"#,
+            ),
+            (
+                HandEvent::BlindPosted {
+                    player: 0,
+                    kind: ForcedBet::SmallBlind,
+                    amount: 5,
+                },
+                r#"{"type": "blind_posted", "player": 0, "kind": "small_blind", "amount": 5}"#,
+            ),
+            (
+                HandEvent::CardsDealt {
+                    player: 1,
+                    cards: vec![c("A0"), c("BC"), c("D3")],
+                    up_cards: vec![c("D3")],
+                },
+                r#"{"type": "cards_dealt", "player": 1, "cards": [0, 25, 42], "up_cards": [42]}"#,
+            ),
+            (
+                HandEvent::ActionTaken {
+                    player: 2,
+                    action: Move::Call,
+                    amount: 40,
+                    total: 50,
+                    is_all_in: true,
+                },
+                r#"{"type": "action_taken", "player": 2, "action": "call", "amount": 40, "total": 50, "is_all_in": true}"#,
+            ),
+            (
+                HandEvent::CardDiscarded {
+                    player: 1,
+                    card: c("BC"),
+                },
+                r#"{"type": "card_discarded", "player": 1, "card": 25}"#,
+            ),
+            (
+                HandEvent::CardsDrawn {
+                    player: 0,
+                    discarded: vec![c("A0"), c("BC")],
+                    drawn: vec![],
+                },
+                r#"{"type": "cards_drawn", "player": 0, "discarded": [0, 25], "drawn": []}"#,
+            ),
+            (
+                HandEvent::BoardDealt {
+                    board: 1,
+                    cards: vec![c("A0")],
+                },
+                r#"{"type": "board_dealt", "board": 1, "cards": [0]}"#,
+            ),
+            (
+                HandEvent::HandShown {
+                    player: 2,
+                    cards: vec![c("D3"), c("A0")],
+                },
+                r#"{"type": "hand_shown", "player": 2, "cards": [42, 0]}"#,
+            ),
+            (
+                HandEvent::BetReturned {
+                    player: 2,
+                    amount: 20,
+                },
+                r#"{"type": "bet_returned", "player": 2, "amount": 20}"#,
+            ),
+            (
+                HandEvent::PotAwarded {
+                    player: 2,
+                    amount: 120,
+                },
+                r#"{"type": "pot_awarded", "player": 2, "amount": 120}"#,
+            ),
+            (
+                HandEvent::HandEnded {
+                    hand_number: 3,
+                    stacks: vec![95, 0, 330, 5],
+                },
+                r#"{"type": "hand_ended", "hand_number": 3, "stacks": [95, 0, 330, 5]}"#,
+            ),
+            (
+                HandEvent::ShuffleRevealed {
+                    hand_number: 3,
+                    server_seed: "0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a"
+                        .to_string(),
+                    client_seeds: vec![None, Some("lucky".to_string())],
+                },
+                r#"{"type": "shuffle_revealed", "hand_number": 3, "server_seed": "0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a", "client_seeds": [null, "lucky"]}"#,
+            ),
+        ];
+
+        for (event, json) in test_cases {
+            let value: Value = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                value,
+                Value::from(event.clone()),
+                "Failed: {:?}. Expected: {}",
+                event,
+                json
+            );
+            assert_eq!(
+                Ok(event.clone()),
+                HandEvent::try_from(value),
+                "Failed: {:?}. Expected to be read back",
+                event
+            );
+        }
+
+        let invalid_cases = vec![
+            r#"{"type": "hand_lost"}"#,
+            r#"{"type": "table_opened", "seed": 7, "players": 2}"#,
+            r#"{"type": "action_applied", "player": 1, "message": {"type": "heartbeat"}}"#,
+            r#"{"type": "action_applied", "message": {"type": "rabbit_hunt"}}"#,
+            r#"{"type": "pot_awarded", "player": 2}"#,
+            r#"{"type": "shuffle_revealed", "hand_number": 3, "server_seed": "0a", "client_seeds": [7]}"#,
+            r#"{"type": "board_dealt", "board": 0, "cards": [52]}"#,
+            r#"{"type": "action_taken", "player": 2, "action": "limp", "amount": 1, "total": 2, "is_all_in": false}"#,
+        ];
+        for json in invalid_cases {
+            let value: Value = serde_json::from_str(json).unwrap();
+            assert!(
+                HandEvent::try_from(value).is_err(),
+                "Failed: {}. Expected an error",
+                json
+            );
+        }
+    }
+
+    /// Testing that a log keeps the events of earlier games.
+    #[test]
+    fn test_append_only_log() {
+        let path = std::env::temp_dir().join(format!("hand_log_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let first = HandEvent::PotAwarded {
+            player: 0,
+            amount: 3,
+        };
+        let second = HandEvent::HandEnded {
+            hand_number: 1,
+            stacks: vec![101, 99],
+        };
+
+        HandLog::open(&path).unwrap().append(first.clone()).unwrap();
+        HandLog::open(&path)
+            .unwrap()
+            .append(second.clone())
+            .unwrap();
+        assert_eq!(Ok(vec![first, second]), HandLog::read(&path));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
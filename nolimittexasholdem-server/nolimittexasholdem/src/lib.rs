@@ -0,0 +1,70 @@
+//! Hand evaluation, game rules and protocol of the No Limit Texas Hold'em server.
+//!
+//! * `cards` and `variant` evaluate hands of every supported variant.
+//! * `equity` and `range` calculate the chances of hands and ranges.
+//! * `engine` plays a game synchronously, without any networking.
+//! * `hand_log` records everything that happened in the hands the engine played.
+//! * `hand_history` exports these hands in formats tracking software understands.
+//! * `replay` plays the logged games once more and checks that the engine still logs the same.
+//! * `raw_message` contains the messages the server and its clients exchange.
+//! * `shuffle` shuffles decks auditably, so players can check every deal afterwards.
+//!
+//! Hands are compared by their values, where a higher value is a better hand:
+//!
+//! ```
+//! use nolimittexasholdem::{Card, GameVariant};
+//!
+//! let cards = |s: &str| -> Vec<Card> { s.split(' ').map(|c| Card::try_from(c).unwrap()).collect() };
+//! let board = cards("A0 B3 C5 D7 A9");
+//! let aces = GameVariant::TexasHoldem.value_of_hand(&cards("BC CC"), &board);
+//! let kings = GameVariant::TexasHoldem.value_of_hand(&cards("BB CB"), &board);
+//! assert!(aces > kings);
+//! ```
+//!
+//! The engine is driven by applying the actions of the players, it answers with events:
+//!
+//! ```
+//! use nolimittexasholdem::betting::{BettingStructure, StraddleRule};
+//! use nolimittexasholdem::engine::{Action, Engine, Event, TableSettings};
+//! use nolimittexasholdem::rotation::Rotation;
+//! use nolimittexasholdem::{GameVariant, PlayerChoice};
+//!
+//! let settings = TableSettings {
+//!     variant: GameVariant::TexasHoldem,
+//!     betting: BettingStructure::NoLimit,
+//!     rotation: Rotation::Fixed,
+//!     straddles: StraddleRule::Off,
+//!     small_blind: 1,
+//!     start_money: 100,
+//!     max_run_outs: 1,
+//!     rabbit_hunt_interval: None,
+//!     bomb_pot: None,
+//!     is_shuffle_audited: false,
+//! };
+//! let mut engine = Engine::new(settings, 2, 7);
+//! let events = engine.start();
+//! // The small blind acts first
+//! assert!(events.iter().any(|e| matches!(e, Event::AwaitingBet { player: 1, .. })));
+//!
+//! engine.apply(Action::Choice { player: 1, choice: PlayerChoice::Fold });
+//! engine.apply(Action::Choice { player: 0, choice: PlayerChoice::Pass });
+//! assert_eq!(101, engine.seats()[0].coins());
+//! ```
+
+pub mod betting;
+pub mod cards;
+pub mod engine;
+pub mod equity;
+pub mod hand_history;
+pub mod hand_log;
+pub mod range;
+pub mod raw_message;
+pub mod replay;
+pub mod rotation;
+pub mod shuffle;
+pub mod variant;
+
+pub use cards::Card;
+pub use engine::{Action, Engine, Event, TableSettings};
+pub use raw_message::{PlayerChoice, RawMessage};
+pub use variant::GameVariant;
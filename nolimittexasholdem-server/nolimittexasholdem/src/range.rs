@@ -0,0 +1,532 @@
+use crate::cards::Card;
+use crate::equity::{self, EquityResult, Tally, BOARD_SIZE, EXHAUSTIVE_LIMIT};
+use crate::variant::GameVariant;
+use itertools::Itertools;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// The characters of the card ranks from lowest (two) to highest (ace).
+const RANKS: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
+
+/// The characters of the card suits in the order of the card colors.
+const SUITS: [char; 4] = ['c', 'd', 'h', 's'];
+
+/// Maximal number of attempts to draw a set of non-overlapping combos in a Monte Carlo simulation.
+const MAX_DRAW_ATTEMPTS: usize = 1_000;
+
+/// A starting hand of two hole cards.
+pub type Combo = [Card; 2];
+
+/// A set of weighted starting hands, parsed from the common range syntax.
+///
+/// A range is a comma separated list of hand classes, each optionally followed by a weight
+/// (e.g. `"TT+, AKs, A5s-A2s, KQo:0.5, 22-55, AhKh"`):
+///
+/// * `TT`, `AK`, `AKs`, `AKo`: a pair, all 16 combos, the suited or the offsuit combos of two ranks
+///
+/// * `TT+`, `ATs+`: the pair and all higher pairs, or the kicker up to one rank below the high card
+///
+/// * `22-55`, `A5s-A2s`: all pairs or all kickers in between
+///
+/// * `AhKh`: a single combo
+///
+/// * `:0.5`: the weight of the class, from 0 (exclusive) to 1 (inclusive), 1 if omitted
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Range {
+    /// The combos of the range with their weights, the higher card first.
+    pub combos: Vec<(Combo, f64)>,
+}
+
+impl Range {
+    /// Returns the amount of combos in the range.
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    /// Returns whether the range contains no combos.
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Returns the sum of the weights of all combos.
+    pub fn total_weight(&self) -> f64 {
+        self.combos.iter().map(|(_, weight)| weight).sum()
+    }
+
+    /// Removes all combos which contain one of the dead cards.
+    pub fn remove_dead(&mut self, dead: &[Card]) {
+        self.combos
+            .retain(|(combo, _)| !combo.iter().any(|card| dead.contains(card)));
+    }
+
+    /// Adds a combo to the range, replacing the weight if the combo is already contained.
+    fn insert(&mut self, mut combo: Combo, weight: f64) {
+        if combo[0].value() < combo[1].value() {
+            combo.swap(0, 1);
+        }
+        match self.combos.iter_mut().find(|(c, _)| *c == combo) {
+            Some(entry) => entry.1 = weight,
+            None => self.combos.push((combo, weight)),
+        }
+    }
+}
+
+impl TryFrom<&str> for Range {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut range = Range::default();
+        for token in value.split(',').map(str::trim) {
+            if token.is_empty() {
+                continue;
+            }
+            let (hands, weight) = match token.split_once(':') {
+                Some((hands, weight)) => {
+                    let weight = weight
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| format!("Invalid weight: {}", token))?;
+                    if !(weight > 0.0 && weight <= 1.0) {
+                        return Err(format!("Weight out of range: {}", token));
+                    }
+                    (hands.trim(), weight)
+                }
+                None => (token, 1.0),
+            };
+            for combo in parse_token(hands)? {
+                range.insert(combo, weight);
+            }
+        }
+        if range.is_empty() {
+            return Err("Range contains no hands".into());
+        }
+        Ok(range)
+    }
+}
+
+/// Which combos of two distinct ranks belong to a hand class.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Suitedness {
+    Suited,
+    Offsuit,
+    Any,
+}
+
+/// A hand class like `AKs` or `TT`, described by its ranks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct HandClass {
+    high: u8,
+    low: u8,
+    suitedness: Suitedness,
+}
+
+impl HandClass {
+    /// Returns whether the class is a pocket pair.
+    fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// Returns all combos of the class.
+    fn combos(&self) -> Vec<Combo> {
+        let mut combos = vec![];
+        for high_suit in 0..4 {
+            for low_suit in 0..4 {
+                let include = if self.is_pair() {
+                    high_suit < low_suit
+                } else {
+                    match self.suitedness {
+                        Suitedness::Suited => high_suit == low_suit,
+                        Suitedness::Offsuit => high_suit != low_suit,
+                        Suitedness::Any => true,
+                    }
+                };
+                if include {
+                    combos.push([
+                        Card::from((high_suit, self.high)),
+                        Card::from((low_suit, self.low)),
+                    ]);
+                }
+            }
+        }
+        combos
+    }
+}
+
+impl TryFrom<&str> for HandClass {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let c: Vec<_> = value.chars().collect();
+        if c.len() != 2 && c.len() != 3 {
+            return Err(format!("Invalid hand: {}", value));
+        }
+        let first = parse_rank(c[0])?;
+        let second = parse_rank(c[1])?;
+        let suitedness = match c.get(2) {
+            None => Suitedness::Any,
+            Some('s') if first != second => Suitedness::Suited,
+            Some('o') if first != second => Suitedness::Offsuit,
+            Some(_) => return Err(format!("Invalid hand: {}", value)),
+        };
+        Ok(Self {
+            high: first.max(second),
+            low: first.min(second),
+            suitedness,
+        })
+    }
+}
+
+/// Parses a single token of a range without its weight into its combos.
+fn parse_token(token: &str) -> Result<Vec<Combo>, String> {
+    let classes = if let Some((from, to)) = token.split_once('-') {
+        let from = HandClass::try_from(from.trim())?;
+        let to = HandClass::try_from(to.trim())?;
+        if from.is_pair() && to.is_pair() {
+            let (low, high) = (from.low.min(to.low), from.low.max(to.low));
+            (low..=high).map(pair).collect()
+        } else if from.high == to.high && from.suitedness == to.suitedness {
+            let (low, high) = (from.low.min(to.low), from.low.max(to.low));
+            (low..=high).map(|low| HandClass { low, ..from }).collect()
+        } else {
+            return Err(format!("Invalid span: {}", token));
+        }
+    } else if let Some(hand) = token.strip_suffix('+') {
+        let class = HandClass::try_from(hand)?;
+        if class.is_pair() {
+            (class.low..RANKS.len() as u8).map(pair).collect()
+        } else {
+            (class.low..class.high)
+                .map(|low| HandClass { low, ..class })
+                .collect()
+        }
+    } else if token.chars().count() == 4 {
+        return Ok(vec![parse_combo(token)?]);
+    } else {
+        vec![HandClass::try_from(token)?]
+    };
+    Ok(classes.iter().flat_map(HandClass::combos).collect())
+}
+
+/// Returns the hand class of a pocket pair.
+fn pair(rank: u8) -> HandClass {
+    HandClass {
+        high: rank,
+        low: rank,
+        suitedness: Suitedness::Any,
+    }
+}
+
+/// Parses a single combo in the form of `AhKh`.
+fn parse_combo(token: &str) -> Result<Combo, String> {
+    let c: Vec<_> = token.chars().collect();
+    let first = Card::from((parse_suit(c[1])?, parse_rank(c[0])?));
+    let second = Card::from((parse_suit(c[3])?, parse_rank(c[2])?));
+    if first == second {
+        return Err(format!("Invalid combo: {}", token));
+    }
+    Ok([first, second])
+}
+
+/// Parses a rank character, e.g. `T` for ten.
+fn parse_rank(c: char) -> Result<u8, String> {
+    RANKS
+        .iter()
+        .position(|&r| r == c.to_ascii_uppercase())
+        .map(|rank| rank as u8)
+        .ok_or_else(|| format!("No rank: {}", c))
+}
+
+/// Parses a suit character, e.g. `h` for hearts.
+fn parse_suit(c: char) -> Result<u8, String> {
+    SUITS
+        .iter()
+        .position(|&s| s == c.to_ascii_lowercase())
+        .map(|suit| suit as u8)
+        .ok_or_else(|| format!("No suit: {}", c))
+}
+
+/// Calculates the equity of ranges against each other.
+///
+/// Combos overlapping with the board or the dead cards are removed from the ranges first,
+/// and combinations of combos sharing a card are skipped.
+/// If at most `EXHAUSTIVE_LIMIT` combinations of combos and run-outs remain, all of them are enumerated,
+/// each weighted by the product of the combo weights.
+/// Otherwise `iterations` combinations are drawn according to the weights with a random generator
+/// seeded by `seed`.
+pub fn calculate_range_equity(
+    ranges: &[Range],
+    board: &[Card],
+    dead: &[Card],
+    iterations: usize,
+    seed: u64,
+) -> Result<EquityResult, String> {
+    if ranges.is_empty() {
+        return Err("No ranges provided".into());
+    }
+    if board.len() > BOARD_SIZE {
+        return Err(format!("Board has too many cards: {}", board.len()));
+    }
+    let known: Vec<Card> = board.iter().chain(dead).copied().collect();
+    // Validates board and dead cards
+    equity::remaining_deck(GameVariant::TexasHoldem, known.iter())?;
+
+    let ranges: Vec<Range> = ranges
+        .iter()
+        .map(|range| {
+            let mut range = range.clone();
+            range.remove_dead(&known);
+            range
+        })
+        .collect();
+    if ranges.iter().any(Range::is_empty) {
+        return Err("A range contains no hands besides the dead cards".into());
+    }
+
+    let missing = BOARD_SIZE - board.len();
+    let cards_left = 52usize
+        .checked_sub(known.len() + 2 * ranges.len())
+        .filter(|&cards_left| cards_left >= missing)
+        .ok_or_else(|| format!("Not enough cards for {} ranges", ranges.len()))?;
+    let combinations = ranges
+        .iter()
+        .map(Range::len)
+        .chain([equity::binomial(cards_left, missing)])
+        .try_fold(1usize, |total, amount| total.checked_mul(amount))
+        .unwrap_or(usize::MAX);
+
+    let mut tallies = vec![Tally::default(); ranges.len()];
+    let mut run_out = board.to_vec();
+    let (total_weight, run_outs, is_exhaustive) = if combinations <= EXHAUSTIVE_LIMIT {
+        let mut total_weight = 0.0;
+        let mut run_outs = 0;
+        for combos in ranges
+            .iter()
+            .map(|r| r.combos.iter())
+            .multi_cartesian_product()
+        {
+            let hands: Vec<Combo> = combos.iter().map(|(combo, _)| *combo).collect();
+            let weight: f64 = combos.iter().map(|(_, weight)| weight).product();
+            let deck = match equity::remaining_deck(
+                GameVariant::TexasHoldem,
+                hands.iter().flatten().chain(&known),
+            ) {
+                Ok(deck) => deck,
+                // Combos share a card
+                Err(_) => continue,
+            };
+            for drawn in deck.iter().combinations(missing) {
+                run_out.truncate(board.len());
+                run_out.extend(drawn);
+                equity::evaluate_run_out(
+                    GameVariant::TexasHoldem,
+                    &hands,
+                    &run_out,
+                    weight,
+                    &mut tallies,
+                );
+                total_weight += weight;
+                run_outs += 1;
+            }
+        }
+        if run_outs == 0 {
+            return Err("Ranges have no hands without overlapping cards".into());
+        }
+        (total_weight, run_outs, true)
+    } else {
+        if iterations == 0 {
+            return Err("At least one iteration is required".into());
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let distributions = ranges
+            .iter()
+            .map(|range| WeightedIndex::new(range.combos.iter().map(|(_, weight)| *weight)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        for _ in 0..iterations {
+            let mut attempts = 0;
+            let (hands, mut deck) = loop {
+                let hands: Vec<Combo> = ranges
+                    .iter()
+                    .zip(&distributions)
+                    .map(|(range, distribution)| range.combos[distribution.sample(&mut rng)].0)
+                    .collect();
+                if let Ok(deck) = equity::remaining_deck(
+                    GameVariant::TexasHoldem,
+                    hands.iter().flatten().chain(&known),
+                ) {
+                    break (hands, deck);
+                }
+                attempts += 1;
+                if attempts >= MAX_DRAW_ATTEMPTS {
+                    return Err("Ranges have no hands without overlapping cards".into());
+                }
+            };
+            let (drawn, _) = deck.partial_shuffle(&mut rng, missing);
+            run_out.truncate(board.len());
+            run_out.extend_from_slice(drawn);
+            equity::evaluate_run_out(
+                GameVariant::TexasHoldem,
+                &hands,
+                &run_out,
+                1.0,
+                &mut tallies,
+            );
+        }
+        (iterations as f64, iterations, false)
+    };
+
+    Ok(EquityResult {
+        equities: tallies
+            .iter()
+            .map(|tally| tally.to_equity(total_weight))
+            .collect(),
+        is_exhaustive,
+        run_outs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equity::{calculate_equity, DEFAULT_ITERATIONS};
+
+    /// Utility function for parsing a string of cards.
+    fn parse(s: &str) -> Vec<Card> {
+        s.split_whitespace()
+            .map(Card::try_from)
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    /// Testing the amount of combos of the range syntax.
+    #[test]
+    fn test_range_sizes() {
+        let test_cases = vec![
+            ("AA", 6),
+            ("AK", 16),
+            ("AKs", 4),
+            ("KQo", 12),
+            ("TT+", 5 * 6),
+            ("22-55", 4 * 6),
+            ("55-22", 4 * 6),
+            ("A5s-A2s", 4 * 4),
+            ("ATs+", 4 * 4),
+            ("K9o+", 4 * 12),
+            ("AhKh", 1),
+            ("TT+, AKs, A5s-A2s, KQo, 22-55", 30 + 4 + 16 + 12 + 24),
+            // Duplicates are only counted once
+            ("AK, AKs, AKo", 16),
+            ("QQ+, KK", 18),
+        ];
+        for (range, expected) in test_cases {
+            let size = Range::try_from(range).unwrap().len();
+            assert_eq!(
+                expected, size,
+                "Failed: {}. Expected {}, got {}",
+                range, expected, size
+            );
+        }
+    }
+
+    /// Testing the parsed combos and weights.
+    #[test]
+    fn test_range_combos() {
+        let range = Range::try_from("AhKh, KK:0.25, AKs:0.5").unwrap();
+        assert_eq!(range.len(), 6 + 4);
+        assert!((range.total_weight() - (6.0 * 0.25 + 4.0 * 0.5)).abs() < 1e-9);
+        // The later class overrides the weight of the single combo
+        let ace_king_hearts = [Card::try_from("CC").unwrap(), Card::try_from("CB").unwrap()];
+        assert!(range.combos.contains(&(ace_king_hearts, 0.5)));
+
+        let mut range = Range::try_from("AA").unwrap();
+        range.remove_dead(&parse("CC"));
+        assert_eq!(range.len(), 3);
+    }
+
+    /// Testing failure of invalid ranges.
+    #[test]
+    fn test_invalid_ranges() {
+        let test_cases = vec![
+            "", "A", "AAs", "AKx", "1K", "AK-QJ", "AKs-A2o", "AK:0", "AK:2", "AK:x", "AhAh", "AxKh",
+        ];
+        for case in test_cases {
+            assert!(
+                Range::try_from(case).is_err(),
+                "Failed: {}. Expected Error, got {:?}",
+                case,
+                Range::try_from(case).unwrap()
+            );
+        }
+    }
+
+    /// Testing that single combo ranges match the equity of the exact hands.
+    #[test]
+    fn test_single_combo_ranges() {
+        let board = parse("C0 D4 A7");
+        let ranges = vec![
+            Range::try_from("AcAd").unwrap(),
+            Range::try_from("KcKd").unwrap(),
+        ];
+        let range_result = calculate_range_equity(&ranges, &board, &[], 1, 0).unwrap();
+        let hand_result = calculate_equity(
+            &[parse("AC BC"), parse("AB BB")],
+            &board,
+            &[],
+            DEFAULT_ITERATIONS,
+            0,
+        )
+        .unwrap();
+        assert!(range_result.is_exhaustive);
+        for (range, hand) in range_result.equities.iter().zip(&hand_result.equities) {
+            assert!((range.share - hand.share).abs() < 1e-9);
+        }
+    }
+
+    /// Testing range equity on the river, including card removal by the board.
+    #[test]
+    fn test_river_range_equity() {
+        // Every ace hits the ace on the board, every king loses
+        let board = parse("CC C1 D5 A7 B9");
+        let ranges = vec![
+            Range::try_from("AA").unwrap(),
+            Range::try_from("KK").unwrap(),
+        ];
+        let result = calculate_range_equity(&ranges, &board, &[], 1, 0).unwrap();
+        assert!(result.is_exhaustive);
+        assert_eq!(result.run_outs, 3 * 6);
+        assert_eq!(result.equities[0].win, 100.0);
+        assert_eq!(result.equities[1].lose, 100.0);
+    }
+
+    /// Testing the Monte Carlo fallback for preflop ranges.
+    #[test]
+    fn test_monte_carlo_range_equity() {
+        // Aces against kings win roughly 82% of the time
+        let ranges = vec![
+            Range::try_from("AA").unwrap(),
+            Range::try_from("KK").unwrap(),
+        ];
+        let result = calculate_range_equity(&ranges, &[], &[], DEFAULT_ITERATIONS, 7).unwrap();
+        assert!(!result.is_exhaustive);
+        assert!((result.equities[0].share - 82.0).abs() < 2.0);
+
+        let ranges = vec![
+            Range::try_from("AA").unwrap(),
+            Range::try_from("AA").unwrap(),
+        ];
+        assert!(calculate_range_equity(&ranges, &[], &parse("AC BC"), 1, 0).is_err());
+
+        // More ranges than the deck has cards for
+        let ranges = vec![Range::try_from("22+").unwrap(); 27];
+        assert_eq!(
+            Err("Not enough cards for 27 ranges".to_string()),
+            calculate_range_equity(&ranges, &[], &[], 1, 0).map(|_| ())
+        );
+        let ranges = vec![Range::try_from("22+").unwrap(); 24];
+        assert!(calculate_range_equity(&ranges, &[], &[], 1, 0).is_err());
+    }
+}
@@ -0,0 +1,763 @@
+use crate::cards::Card;
+use serde_json::{json, Value};
+
+/// An enumeration type for representing the types of messages.
+///
+/// Messages are sent as JSON objects:
+///
+/// ```
+/// use nolimittexasholdem::{PlayerChoice, RawMessage};
+/// use serde_json::{json, Value};
+///
+/// let message = RawMessage::PlayerChoice(PlayerChoice::RaiseTo(40));
+/// let value = Value::from(message.clone());
+/// assert_eq!(json!({"type": "response", "action": "raise_to", "amount": 40}), value);
+/// assert_eq!(message, RawMessage::try_from(value).unwrap());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum RawMessage {
+    SetDisplayName(String),
+    IsReady,
+    Heartbeat,
+    PlayerChoice(PlayerChoice),
+    ConnectionEnded,
+    AwaitingPlayer {
+        minimal_amount: usize,
+        maximal_amount: usize,
+    },
+    AwaitingRunItTimes(usize),
+    AwaitingDiscard,
+    AwaitingDraw,
+    AwaitingVariant(Vec<String>),
+    AwaitingStraddle(usize),
+    AwaitingBombPotVote,
+    RunItTimes(usize),
+    RabbitHunt,
+    ShuffleCommitment {
+        hand_number: usize,
+        commitment: String,
+    },
+    ClientSeed(String),
+    ShuffleReveal {
+        hand_number: usize,
+        server_seed: String,
+        client_seeds: Vec<Option<String>>,
+    },
+    RabbitHuntResult {
+        requested_by: usize,
+        cards: Vec<Card>,
+    },
+    GameStatus {
+        personal_cards: Vec<Card>,
+        personal_id: usize,
+        middle_cards: Vec<Card>,
+        player_names: Vec<String>,
+        variant: String,
+        next_variant: Option<String>,
+        hands_until_next_variant: Option<usize>,
+        is_bomb_pot: bool,
+        player_cards: Vec<Option<Vec<Card>>>,
+        player_up_cards: Vec<Vec<Card>>,
+        player_betting_amount: Vec<usize>,
+        player_money: Vec<usize>,
+        player_has_folded: Vec<bool>,
+        player_is_out: Vec<bool>,
+        round_number: usize,
+        is_started: bool,
+        hand_winner: i8,
+        is_showdown: bool,
+        player_equity: Vec<Option<f64>>,
+        is_all_in_run_out: bool,
+        extra_boards: Vec<Vec<Card>>,
+        high_winners: Vec<Vec<usize>>,
+        low_winners: Vec<Vec<usize>>,
+    },
+    GameEnd(Option<usize>),
+    SessionToken(String),
+    ReclaimSeat(String),
+    Pause,
+    Resume,
+    TablePaused(usize),
+    TableResumed(usize),
+    TableClosed {
+        is_hand_refunded: bool,
+    },
+}
+
+/// An enumeration type for representing the choices of the player during a poker round.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum PlayerChoice {
+    RaiseTo(usize),
+    Fold,
+    Pass,
+    Discard(Card),
+    Draw(Vec<Card>),
+    ChooseVariant(String),
+    Straddle(bool),
+    BombPot(bool),
+}
+
+impl From<RawMessage> for Value {
+    fn from(message: RawMessage) -> Self {
+        match message {
+            RawMessage::SetDisplayName(s) => json!({"type": "set_display_name", "player_name" : s}),
+            RawMessage::IsReady => json!({"type": "is_ready"}),
+            RawMessage::Heartbeat => json!({"type": "heartbeat"}),
+            RawMessage::PlayerChoice(s) => match s {
+                PlayerChoice::RaiseTo(val) => {
+                    json!({"type": "response",
+                        "action" : "raise_to",
+                        "amount" : val
+                    })
+                }
+                PlayerChoice::Fold => {
+                    json!({"type": "response",
+                        "action" : "fold"
+                    })
+                }
+                PlayerChoice::Pass => {
+                    json!({"type": "response",
+                        "action" : "pass",
+                    })
+                }
+                PlayerChoice::Discard(card) => {
+                    json!({"type": "response",
+                        "action" : "discard",
+                        "card" : card.value
+                    })
+                }
+                PlayerChoice::Draw(cards) => {
+                    let cards: Vec<u8> = cards.into_iter().map(|c| c.value).collect();
+                    json!({"type": "response",
+                        "action" : "draw",
+                        "cards" : cards
+                    })
+                }
+                PlayerChoice::ChooseVariant(variant) => {
+                    json!({"type": "response",
+                        "action" : "choose_variant",
+                        "variant" : variant
+                    })
+                }
+                PlayerChoice::Straddle(is_straddling) => {
+                    json!({"type": "response",
+                        "action" : "straddle",
+                        "enabled" : is_straddling
+                    })
+                }
+                PlayerChoice::BombPot(is_voting_for) => {
+                    json!({"type": "response",
+                        "action" : "bomb_pot",
+                        "enabled" : is_voting_for
+                    })
+                }
+            },
+            RawMessage::ConnectionEnded => json!({"type": "connection_ended"}),
+            RawMessage::AwaitingPlayer {
+                minimal_amount,
+                maximal_amount,
+            } => json!({
+                "type": "awaiting_player",
+                "minimal_amount": minimal_amount,
+                "maximal_amount": maximal_amount
+            }),
+            RawMessage::AwaitingRunItTimes(max_times) => {
+                json!({"type": "awaiting_run_it", "max_times": max_times})
+            }
+            RawMessage::RunItTimes(times) => json!({"type": "run_it", "times": times}),
+            RawMessage::AwaitingDiscard => json!({"type": "awaiting_discard"}),
+            RawMessage::AwaitingDraw => json!({"type": "awaiting_draw"}),
+            RawMessage::AwaitingVariant(variants) => {
+                json!({"type": "awaiting_variant", "variants": variants})
+            }
+            RawMessage::AwaitingStraddle(amount) => {
+                json!({"type": "awaiting_straddle", "amount": amount})
+            }
+            RawMessage::AwaitingBombPotVote => json!({"type": "awaiting_bomb_pot_vote"}),
+            RawMessage::RabbitHunt => json!({"type": "rabbit_hunt"}),
+            RawMessage::ShuffleCommitment {
+                hand_number,
+                commitment,
+            } => json!({
+                "type": "shuffle_commitment",
+                "hand_number": hand_number,
+                "commitment": commitment
+            }),
+            RawMessage::ClientSeed(seed) => json!({"type": "client_seed", "seed": seed}),
+            RawMessage::ShuffleReveal {
+                hand_number,
+                server_seed,
+                client_seeds,
+            } => json!({
+                "type": "shuffle_reveal",
+                "hand_number": hand_number,
+                "server_seed": server_seed,
+                "client_seeds": client_seeds
+            }),
+            RawMessage::RabbitHuntResult {
+                requested_by,
+                cards,
+            } => {
+                let cards: Vec<u8> = cards.into_iter().map(|c| c.value).collect();
+                json!({"type": "rabbit_hunt_result", "requested_by": requested_by, "cards": cards})
+            }
+            RawMessage::GameStatus {
+                personal_cards,
+                personal_id,
+                middle_cards,
+                player_names,
+                variant,
+                next_variant,
+                hands_until_next_variant,
+                is_bomb_pot,
+                player_cards,
+                player_up_cards,
+                player_betting_amount,
+                player_money,
+                player_has_folded,
+                player_is_out,
+                round_number,
+                is_started,
+                hand_winner,
+                is_showdown,
+                player_equity,
+                is_all_in_run_out,
+                extra_boards,
+                high_winners,
+                low_winners,
+            } => {
+                let personal_cards: Vec<u8> = personal_cards.into_iter().map(|c| c.value).collect();
+                let middle_cards: Vec<u8> = middle_cards.into_iter().map(|c| c.value).collect();
+                let extra_boards: Vec<Vec<u8>> = extra_boards
+                    .into_iter()
+                    .map(|board| board.into_iter().map(|c| c.value).collect())
+                    .collect();
+                let player_cards: Vec<Option<Vec<u8>>> = player_cards
+                    .into_iter()
+                    .map(|c| c.map(|t| t.into_iter().map(|c| c.value).collect()))
+                    .collect();
+                let player_up_cards: Vec<Vec<u8>> = player_up_cards
+                    .into_iter()
+                    .map(|t| t.into_iter().map(|c| c.value).collect())
+                    .collect();
+                json!({
+                    "type": "game_state",
+                    "personal_cards": personal_cards,
+                    "personal_id": personal_id,
+                    "middle_cards": middle_cards,
+                    "player_names": player_names,
+                    "variant": variant,
+                    "next_variant": next_variant,
+                    "hands_until_next_variant": hands_until_next_variant,
+                    "is_bomb_pot": is_bomb_pot,
+                    "player_cards": player_cards,
+                    "player_up_cards": player_up_cards,
+                    "player_betting_amount": player_betting_amount,
+                    "player_money": player_money,
+                    "player_has_folded": player_has_folded,
+                    "player_is_out": player_is_out,
+                    "round_number": round_number,
+                    "is_started": is_started,
+                    "hand_winner": hand_winner,
+                    "is_showdown": is_showdown,
+                    "player_equity": player_equity,
+                    "is_all_in_run_out": is_all_in_run_out,
+                    "extra_boards": extra_boards,
+                    "high_winners": high_winners,
+                    "low_winners": low_winners
+                })
+            }
+            RawMessage::GameEnd(winner) => {
+                json!({"type": "game_end", "winner":winner})
+            }
+            RawMessage::SessionToken(token) => json!({"type": "session_token", "token": token}),
+            RawMessage::ReclaimSeat(token) => json!({"type": "reclaim_seat", "token": token}),
+            RawMessage::Pause => json!({"type": "pause"}),
+            RawMessage::Resume => json!({"type": "resume"}),
+            RawMessage::TablePaused(player) => json!({"type": "table_paused", "player": player}),
+            RawMessage::TableResumed(player) => json!({"type": "table_resumed", "player": player}),
+            RawMessage::TableClosed { is_hand_refunded } => {
+                json!({"type": "table_closed", "is_hand_refunded": is_hand_refunded})
+            }
+        }
+    }
+}
+
+impl TryFrom<Value> for RawMessage {
+    type Error = serde_json::Error;
+
+    fn try_from(mut value: Value) -> Result<RawMessage, Self::Error> {
+        use serde_json::value::from_value;
+
+        match from_value::<String>(value["type"].take())?.as_str() {
+            "is_ready" => Ok(Self::IsReady),
+            "heartbeat" => Ok(Self::Heartbeat),
+            "awaiting_player" => Ok(Self::AwaitingPlayer {
+                minimal_amount: from_value::<u64>(value["minimal_amount"].take())? as usize,
+                maximal_amount: from_value::<u64>(value["maximal_amount"].take())? as usize,
+            }),
+            "awaiting_discard" => Ok(Self::AwaitingDiscard),
+            "awaiting_draw" => Ok(Self::AwaitingDraw),
+            "awaiting_variant" => Ok(Self::AwaitingVariant(from_value(value["variants"].take())?)),
+            "awaiting_straddle" => Ok(Self::AwaitingStraddle(from_value::<u64>(
+                value["amount"].take(),
+            )? as usize)),
+            "awaiting_bomb_pot_vote" => Ok(Self::AwaitingBombPotVote),
+            "awaiting_run_it" => Ok(Self::AwaitingRunItTimes(from_value::<u64>(
+                value["max_times"].take(),
+            )? as usize)),
+            "run_it" => Ok(Self::RunItTimes(
+                from_value::<u64>(value["times"].take())? as usize
+            )),
+            "rabbit_hunt" => Ok(Self::RabbitHunt),
+            "shuffle_commitment" => Ok(Self::ShuffleCommitment {
+                hand_number: from_value::<u64>(value["hand_number"].take())? as usize,
+                commitment: from_value(value["commitment"].take())?,
+            }),
+            "client_seed" => Ok(Self::ClientSeed(from_value(value["seed"].take())?)),
+            "session_token" => Ok(Self::SessionToken(from_value(value["token"].take())?)),
+            "reclaim_seat" => Ok(Self::ReclaimSeat(from_value(value["token"].take())?)),
+            "pause" => Ok(Self::Pause),
+            "resume" => Ok(Self::Resume),
+            "table_paused" => Ok(Self::TablePaused(
+                from_value::<u64>(value["player"].take())? as usize,
+            )),
+            "table_resumed" => Ok(Self::TableResumed(
+                from_value::<u64>(value["player"].take())? as usize,
+            )),
+            "table_closed" => Ok(Self::TableClosed {
+                is_hand_refunded: from_value(value["is_hand_refunded"].take())?,
+            }),
+            "shuffle_reveal" => Ok(Self::ShuffleReveal {
+                hand_number: from_value::<u64>(value["hand_number"].take())? as usize,
+                server_seed: from_value(value["server_seed"].take())?,
+                client_seeds: from_value(value["client_seeds"].take())?,
+            }),
+            "rabbit_hunt_result" => Ok(Self::RabbitHuntResult {
+                requested_by: from_value(value["requested_by"].take())?,
+                cards: from_value::<Vec<u8>>(value["cards"].take())?
+                    .into_iter()
+                    .map(|value| Card { value })
+                    .collect(),
+            }),
+            "game_end" => Ok(Self::GameEnd(from_value(value["winner"].take())?)),
+            "set_display_name" => Ok(Self::SetDisplayName(from_value(
+                value["player_name"].take(),
+            )?)),
+            "connection_ended" => Ok(Self::ConnectionEnded),
+            "response" => {
+                match from_value::<String>(value["action"].take())?.as_str() {
+                    "raise_to" => Ok(Self::PlayerChoice(PlayerChoice::RaiseTo(
+                        from_value::<u64>(value["amount"].take())? as usize,
+                    ))),
+                    "fold" => Ok(Self::PlayerChoice(PlayerChoice::Fold)),
+                    "pass" => Ok(Self::PlayerChoice(PlayerChoice::Pass)),
+                    "discard" => Ok(Self::PlayerChoice(PlayerChoice::Discard(Card {
+                        value: from_value(value["card"].take())?,
+                    }))),
+                    "draw" => Ok(Self::PlayerChoice(PlayerChoice::Draw(
+                        from_value::<Vec<u8>>(value["cards"].take())?
+                            .into_iter()
+                            .map(|value| Card { value })
+                            .collect(),
+                    ))),
+                    "choose_variant" => Ok(Self::PlayerChoice(PlayerChoice::ChooseVariant(
+                        from_value(value["variant"].take())?,
+                    ))),
+                    "straddle" => Ok(Self::PlayerChoice(PlayerChoice::Straddle(from_value(
+                        value["enabled"].take(),
+                    )?))),
+                    "bomb_pot" => Ok(Self::PlayerChoice(PlayerChoice::BombPot(from_value(
+                        value["enabled"].take(),
+                    )?))),
+                    _ => {
+                        // Will return an Err
+                        // Must use weird logic here, as constructing an error is not possible
+                        // serde_json has made error creation private
+                        from_value::<String>(Value::Null)?;
+                        panic!("Should not reach this code");
+                    }
+                }
+            }
+            "game_state" => {
+                let u64_to_card: fn(&u64) -> Card = |c| Card { value: *c as u8 };
+
+                let personal_cards: Vec<Card> =
+                    from_value::<Vec<u64>>(value["personal_cards"].take())?
+                        .iter()
+                        .map(u64_to_card)
+                        .collect();
+
+                let personal_id: usize = from_value(value["personal_id"].take())?;
+
+                let middle_cards: Vec<Card> = from_value::<Vec<u64>>(value["middle_cards"].take())?
+                    .iter()
+                    .map(u64_to_card)
+                    .collect();
+
+                let player_names = from_value::<Vec<String>>(value["player_names"].take())?;
+                let variant: String = from_value(value["variant"].take())?;
+                let next_variant: Option<String> = from_value(value["next_variant"].take())?;
+                let hands_until_next_variant: Option<usize> =
+                    from_value(value["hands_until_next_variant"].take())?;
+                let is_bomb_pot: bool = from_value(value["is_bomb_pot"].take())?;
+
+                let player_cards: Vec<Option<Vec<Card>>> =
+                    from_value::<Vec<Option<Vec<u64>>>>(value["player_cards"].take())?
+                        .into_iter()
+                        .map(|v| v.map(|t| t.iter().map(u64_to_card).collect()))
+                        .collect();
+
+                let player_up_cards: Vec<Vec<Card>> =
+                    from_value::<Vec<Vec<u64>>>(value["player_up_cards"].take())?
+                        .iter()
+                        .map(|t| t.iter().map(u64_to_card).collect())
+                        .collect();
+
+                let player_betting_amount =
+                    from_value::<Vec<u64>>(value["player_betting_amount"].take())?
+                        .into_iter()
+                        .map(|a| a as usize)
+                        .collect();
+                let player_money = from_value::<Vec<u64>>(value["player_money"].take())?
+                    .into_iter()
+                    .map(|a| a as usize)
+                    .collect();
+                let player_has_folded = from_value::<Vec<bool>>(value["player_has_folded"].take())?;
+                let player_is_out = from_value::<Vec<bool>>(value["player_is_out"].take())?;
+
+                let round_number: usize = from_value(value["round_number"].take())?;
+                let is_started: bool = from_value(value["is_started"].take())?;
+                let hand_winner: i8 = from_value(value["hand_winner"].take())?;
+                let is_showdown: bool = from_value(value["is_showdown"].take())?;
+                let player_equity = from_value::<Vec<Option<f64>>>(value["player_equity"].take())?;
+                let is_all_in_run_out: bool = from_value(value["is_all_in_run_out"].take())?;
+                let extra_boards: Vec<Vec<Card>> =
+                    from_value::<Vec<Vec<u64>>>(value["extra_boards"].take())?
+                        .iter()
+                        .map(|board| board.iter().map(u64_to_card).collect())
+                        .collect();
+                let high_winners = from_value::<Vec<Vec<usize>>>(value["high_winners"].take())?;
+                let low_winners = from_value::<Vec<Vec<usize>>>(value["low_winners"].take())?;
+
+                Ok(Self::GameStatus {
+                    personal_cards,
+                    personal_id,
+                    middle_cards,
+                    player_names,
+                    variant,
+                    next_variant,
+                    hands_until_next_variant,
+                    is_bomb_pot,
+                    player_cards,
+                    player_up_cards,
+                    player_betting_amount,
+                    player_money,
+                    player_has_folded,
+                    player_is_out,
+                    round_number,
+                    is_started,
+                    hand_winner,
+                    is_showdown,
+                    player_equity,
+                    is_all_in_run_out,
+                    extra_boards,
+                    high_winners,
+                    low_winners,
+                })
+            }
+            _ => {
+                // Will return an Err
+                // Must use weird logic here, as constructing an error is not possible
+                // serde_json has made error creation private
+                from_value::<String>(Value::Null)?;
+                panic!("Should not reach this code");
+            }
+        }
+    }
+}
+
+impl RawMessage {
+    /// Encodes the RawMessage as it is sent, prepended by the length of the message and a colon.
+    pub fn encode(self) -> String {
+        let t: Value = self.into();
+        let s = t.to_string();
+        // Prepend the length of the message
+
+        let mut message = s.len().to_string();
+
+        message.push(':');
+        message.push_str(&s);
+        message
+    }
+
+    /// Decodes a RawMessage from a received message, without the length in front of it.
+    pub fn decode(message: &str) -> Result<Self, serde_json::Error> {
+        let value_msg: Value = serde_json::from_str(message)?;
+        RawMessage::try_from(value_msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Testing RawMessage to JSON string conversion and vice versa.
+    #[test]
+    fn test_raw_message_conversion() {
+        let messages = vec![
+            (
+                RawMessage::SetDisplayName("TestUsername1$".to_string()),
+                r#"{"type":"set_display_name", "player_name": "TestUsername1$"}"#,
+            ),
+            (RawMessage::IsReady, r#"{"type":"is_ready"}"#),
+            (RawMessage::Heartbeat, r#"{"type":"heartbeat"}"#),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::RaiseTo(3)),
+                r#"{"type": "response", "action" : "raise_to", "amount" : 3}"#,
+            ),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::Fold),
+                r#"{"type": "response", "action" : "fold"}"#,
+            ),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::Pass),
+                r#"{"type": "response", "action" : "pass"}"#,
+            ),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::Discard(Card::try_from("BC").unwrap())),
+                r#"{"type": "response", "action" : "discard", "card" : 25}"#,
+            ),
+            (
+                RawMessage::AwaitingDiscard,
+                r#"{"type": "awaiting_discard"}"#,
+            ),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::Draw(vec![
+                    Card::try_from("A0").unwrap(),
+                    Card::try_from("BC").unwrap(),
+                ])),
+                r#"{"type": "response", "action" : "draw", "cards" : [0, 25]}"#,
+            ),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::Draw(vec![])),
+                r#"{"type": "response", "action" : "draw", "cards" : []}"#,
+            ),
+            (RawMessage::AwaitingDraw, r#"{"type": "awaiting_draw"}"#),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::ChooseVariant("razz".to_string())),
+                r#"{"type": "response", "action" : "choose_variant", "variant" : "razz"}"#,
+            ),
+            (
+                RawMessage::AwaitingVariant(vec!["holdem".to_string(), "stud8".to_string()]),
+                r#"{"type": "awaiting_variant", "variants": ["holdem", "stud8"]}"#,
+            ),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::Straddle(true)),
+                r#"{"type": "response", "action" : "straddle", "enabled" : true}"#,
+            ),
+            (
+                RawMessage::AwaitingStraddle(8),
+                r#"{"type": "awaiting_straddle", "amount": 8}"#,
+            ),
+            (
+                RawMessage::PlayerChoice(PlayerChoice::BombPot(false)),
+                r#"{"type": "response", "action" : "bomb_pot", "enabled" : false}"#,
+            ),
+            (
+                RawMessage::AwaitingBombPotVote,
+                r#"{"type": "awaiting_bomb_pot_vote"}"#,
+            ),
+            (
+                RawMessage::ConnectionEnded,
+                r#"{"type": "connection_ended"}"#,
+            ),
+            (
+                RawMessage::AwaitingPlayer {
+                    minimal_amount: 20,
+                    maximal_amount: 60,
+                },
+                r#"{"type": "awaiting_player", "minimal_amount": 20, "maximal_amount": 60}"#,
+            ),
+            (
+                RawMessage::AwaitingRunItTimes(3),
+                r#"{"type": "awaiting_run_it", "max_times": 3}"#,
+            ),
+            (
+                RawMessage::RunItTimes(2),
+                r#"{"type": "run_it", "times": 2}"#,
+            ),
+            (RawMessage::RabbitHunt, r#"{"type": "rabbit_hunt"}"#),
+            (
+                RawMessage::ShuffleCommitment {
+                    hand_number: 4,
+                    commitment: "ab01".to_string(),
+                },
+                r#"{"type": "shuffle_commitment", "hand_number": 4, "commitment": "ab01"}"#,
+            ),
+            (
+                RawMessage::ClientSeed("lucky".to_string()),
+                r#"{"type": "client_seed", "seed": "lucky"}"#,
+            ),
+            (
+                RawMessage::SessionToken("5f3a".to_string()),
+                r#"{"type": "session_token", "token": "5f3a"}"#,
+            ),
+            (
+                RawMessage::ReclaimSeat("5f3a".to_string()),
+                r#"{"type": "reclaim_seat", "token": "5f3a"}"#,
+            ),
+            (RawMessage::Pause, r#"{"type": "pause"}"#),
+            (RawMessage::Resume, r#"{"type": "resume"}"#),
+            (
+                RawMessage::TablePaused(2),
+                r#"{"type": "table_paused", "player": 2}"#,
+            ),
+            (
+                RawMessage::TableResumed(0),
+                r#"{"type": "table_resumed", "player": 0}"#,
+            ),
+            (
+                RawMessage::TableClosed {
+                    is_hand_refunded: true,
+                },
+                r#"{"type": "table_closed", "is_hand_refunded": true}"#,
+            ),
+            (
+                RawMessage::ShuffleReveal {
+                    hand_number: 4,
+                    server_seed: "cd23".to_string(),
+                    client_seeds: vec![Some("lucky".to_string()), None],
+                },
+                r#"{"type": "shuffle_reveal", "hand_number": 4, "server_seed": "cd23", "client_seeds": ["lucky", null]}"#,
+            ),
+            (
+                RawMessage::RabbitHuntResult {
+                    requested_by: 2,
+                    cards: vec![Card::try_from("A0").unwrap(), Card::try_from("D3").unwrap()],
+                },
+                r#"{"type": "rabbit_hunt_result", "requested_by": 2, "cards": [0, 42]}"#,
+            ),
+            (
+                RawMessage::GameStatus {
+                    personal_cards: vec![
+                        Card::try_from("CA").unwrap(),
+                        Card::try_from("D4").unwrap(),
+                    ],
+                    personal_id: 1234,
+                    middle_cards: vec![
+                        Card::try_from("A0").unwrap(),
+                        Card::try_from("B1").unwrap(),
+                        Card::try_from("A3").unwrap(),
+                    ],
+                    player_names: vec![
+                        "user1".to_string(),
+                        "User2".to_string(),
+                        "User3$".to_string(),
+                    ],
+                    variant: "plo8".to_string(),
+                    next_variant: Some("razz".to_string()),
+                    hands_until_next_variant: Some(3),
+                    is_bomb_pot: true,
+                    player_cards: vec![
+                        Some(vec![
+                            Card::try_from("BB").unwrap(),
+                            Card::try_from("DB").unwrap(),
+                        ]),
+                        Some(vec![
+                            Card::try_from("A2").unwrap(),
+                            Card::try_from("B3").unwrap(),
+                            Card::try_from("C4").unwrap(),
+                            Card::try_from("D5").unwrap(),
+                        ]),
+                        None,
+                    ],
+                    player_up_cards: vec![vec![], vec![Card::try_from("C4").unwrap()], vec![]],
+                    player_betting_amount: vec![2, 3, 4],
+                    player_money: vec![100, 100, 200],
+                    player_has_folded: vec![true, false, true],
+                    player_is_out: vec![true, true, false],
+                    round_number: 2,
+                    is_started: true,
+                    hand_winner: -1,
+                    is_showdown: false,
+                    player_equity: vec![Some(37.5), None, None],
+                    is_all_in_run_out: true,
+                    extra_boards: vec![vec![
+                        Card::try_from("A0").unwrap(),
+                        Card::try_from("B1").unwrap(),
+                        Card::try_from("D3").unwrap(),
+                    ]],
+                    high_winners: vec![vec![1], vec![1, 2]],
+                    low_winners: vec![vec![2], vec![]],
+                },
+                r#"{
+                "type": "game_state",
+                "personal_cards": [36, 43],
+                "personal_id": 1234,
+                "middle_cards": [0, 14, 3],
+                "player_names": ["user1", "User2", "User3$"],
+                "variant": "plo8",
+                "next_variant": "razz",
+                "hands_until_next_variant": 3,
+                "is_bomb_pot": true,
+                "player_cards": [[24,50], [2, 16, 30, 44], null],
+                "player_up_cards": [[], [30], []],
+                "player_betting_amount": [2, 3, 4],
+                "player_money": [100, 100, 200],
+                "player_has_folded": [true, false, true],
+                "player_is_out": [true, true, false],
+                "round_number": 2,
+                "is_started": true,
+                "hand_winner": -1,
+                "is_showdown": false,
+                "player_equity": [37.5, null, null],
+                "is_all_in_run_out": true,
+                "extra_boards": [[0, 14, 42]],
+                "high_winners": [[1], [1, 2]],
+                "low_winners": [[2], []]
+             }"#,
+            ),
+            (
+                RawMessage::GameEnd(Some(1)),
+                r#"{"type": "game_end", "winner": 1}"#,
+            ),
+        ];
+
+        for (raw_message, json_message) in messages {
+            let json: Value = raw_message.clone().into();
+            let validation_json: Value = serde_json::from_str(json_message).unwrap();
+            assert_eq!(
+                json, validation_json,
+                "Failed: {:?}. Expected {}, got {}",
+                raw_message, json_message, json
+            );
+            let converted_to_raw_message = RawMessage::try_from(json.clone()).unwrap();
+            assert_eq!(
+                converted_to_raw_message, raw_message,
+                "Failed: {}. Expected {:?}, got {:?}",
+                json, raw_message, converted_to_raw_message
+            );
+        }
+    }
+
+    /// Testing that messages are prepended by their length in bytes and decoded without it.
+    #[test]
+    fn test_encode() {
+        let messages = vec![
+            (RawMessage::IsReady, r#"19:{"type":"is_ready"}"#),
+            (
+                RawMessage::SetDisplayName("Ä".to_string()),
+                r#"46:{"player_name":"Ä","type":"set_display_name"}"#,
+            ),
+        ];
+        for (raw_message, expected) in messages {
+            let message = raw_message.clone().encode();
+            assert_eq!(expected, message, "Failed: {:?}", raw_message);
+            let (_, payload) = message.split_once(':').unwrap();
+            assert_eq!(raw_message, RawMessage::decode(payload).unwrap());
+        }
+        assert!(RawMessage::decode(r#"{"type":"unknown"}"#).is_err());
+    }
+}
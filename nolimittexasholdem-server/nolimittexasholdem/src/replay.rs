@@ -0,0 +1,508 @@
+use crate::engine::{Engine, Event};
+use crate::hand_log::HandEvent;
+use crate::shuffle::{self, Seed};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fmt::Formatter;
+
+/// What the replay of a hand log covered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReplaySummary {
+    /// The amount of tables opened or restored in the log.
+    pub games: usize,
+
+    /// The amount of hands which ended.
+    pub hands: usize,
+
+    /// The amount of actions applied to the engines.
+    pub actions: usize,
+}
+
+/// The first event in which a replay differs from the hand log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// The position of the event in the log, starting with 1.
+    pub position: usize,
+
+    /// The number of the hand which was played at that point, if any.
+    pub hand_number: Option<usize>,
+
+    /// The event of the log, or `None` if the log ended.
+    pub recorded: Option<HandEvent>,
+
+    /// The event the engine logged instead, or `None` if it waited for an action.
+    pub replayed: Option<HandEvent>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let describe = |event: &Option<HandEvent>, otherwise: &str| match event {
+            Some(event) => Value::from(event.clone()).to_string(),
+            None => otherwise.to_string(),
+        };
+        if let Some(hand_number) = self.hand_number {
+            write!(f, "Hand {}, ", hand_number)?;
+        }
+        write!(
+            f,
+            "event {}: the log has {}, but the engine logged {}",
+            self.position,
+            describe(&self.recorded, "nothing more"),
+            describe(&self.replayed, "nothing before the next action")
+        )
+    }
+}
+
+/// Why a hand log could not be replayed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplayError {
+    /// The engine logged something else than the hand log.
+    Diverged(Box<Divergence>),
+
+    /// The audited shuffle of the hand was never revealed, as the hand was interrupted or refunded.
+    /// Without its server seed, the cards of the hand can not be dealt once more.
+    Unrevealed { position: usize, hand_number: usize },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Diverged(divergence) => write!(f, "{}", divergence),
+            Self::Unrevealed {
+                position,
+                hand_number,
+            } => write!(
+                f,
+                "Hand {}, event {}: the audited hand can not be replayed, its shuffle was never revealed",
+                hand_number, position
+            ),
+        }
+    }
+}
+
+/// Plays the games of the hand log once more and checks that the engine logs exactly what was recorded:
+/// the same cards, bets, pots, winners and stacks.
+/// Every opened or restored table starts a new engine, to which the recorded actions are applied.
+/// A restored table abandons the game before it, whose last hand may have been interrupted by a crash.
+/// Once a table was closed, the rest of its game is not played.
+/// Events before the first opened table can not be replayed and are skipped.
+///
+/// Audited hands are shuffled with the server seeds revealed after them.
+/// Replaying a hand whose shuffle was never revealed fails.
+pub fn replay(events: &[HandEvent]) -> Result<ReplaySummary, ReplayError> {
+    let mut summary = ReplaySummary::default();
+    let mut engine: Option<Engine> = None;
+    let mut pending: VecDeque<HandEvent> = VecDeque::new();
+    let mut hand_number = None;
+    // The server seeds revealed in the game of the current table, if its shuffle is audited
+    let mut revealed_seeds: Option<HashMap<usize, Seed>> = None;
+    for (i, event) in events.iter().enumerate() {
+        let diverge = |replayed: Option<&HandEvent>| {
+            ReplayError::Diverged(Box::new(Divergence {
+                position: i + 1,
+                hand_number,
+                recorded: Some(event.clone()),
+                replayed: replayed.cloned(),
+            }))
+        };
+        let opened_settings = match event {
+            HandEvent::TableOpened { settings, .. } => Some(settings),
+            HandEvent::TableRestored { state, .. } => Some(&state.settings),
+            _ => None,
+        };
+        if let Some(settings) = opened_settings {
+            revealed_seeds = settings
+                .is_shuffle_audited
+                .then(|| find_revealed_seeds(&events[i + 1..]));
+        }
+        if let Some((new_engine, events)) = open_table(event, revealed_seeds.clone()) {
+            let is_restored = matches!(event, HandEvent::TableRestored { .. });
+            if let Some(replayed) = pending.front().filter(|_| !is_restored) {
+                return Err(diverge(Some(replayed)));
+            }
+            pending = logged(events);
+            engine = Some(new_engine);
+            hand_number = None;
+            summary.games += 1;
+            continue;
+        }
+        match event {
+            _ if engine.is_none() => { /* Recorded before the table was logged */ }
+            HandEvent::TableClosed { .. } => {
+                pending.clear();
+                engine = None;
+            }
+            HandEvent::ActionApplied { action } => {
+                if let Some(replayed) = pending.front() {
+                    return Err(diverge(Some(replayed)));
+                }
+                pending = logged(engine.as_mut().unwrap().apply(action.clone()));
+                summary.actions += 1;
+            }
+            // The cards of the hand are unknown, so none of them are compared
+            HandEvent::HandStarted { hand_number: n, .. }
+                if revealed_seeds
+                    .as_ref()
+                    .is_some_and(|seeds| !seeds.contains_key(n)) =>
+            {
+                return Err(ReplayError::Unrevealed {
+                    position: i + 1,
+                    hand_number: *n,
+                });
+            }
+            event => {
+                let replayed = pending.pop_front();
+                if replayed.as_ref() != Some(event) {
+                    return Err(diverge(replayed.as_ref()));
+                }
+                match event {
+                    HandEvent::HandStarted { hand_number: n, .. } => hand_number = Some(*n),
+                    HandEvent::HandEnded { .. } => summary.hands += 1,
+                    _ => {}
+                }
+            }
+        }
+    }
+    match pending.pop_front() {
+        Some(replayed) => Err(ReplayError::Diverged(Box::new(Divergence {
+            position: events.len() + 1,
+            hand_number,
+            recorded: None,
+            replayed: Some(replayed),
+        }))),
+        None => Ok(summary),
+    }
+}
+
+/// The secret with which a replay shuffles the audited hands whose shuffle was never revealed.
+/// The replay fails once such a hand starts, before any of its cards are compared.
+const UNREVEALED_SECRET: Seed = [0; 32];
+
+/// Starts the engine of a table which was opened or restored, together with its first events.
+/// Audited hands are shuffled with the revealed seeds.
+fn open_table(
+    event: &HandEvent,
+    revealed_seeds: Option<HashMap<usize, Seed>>,
+) -> Option<(Engine, Vec<Event>)> {
+    let revealed_seeds = revealed_seeds.unwrap_or_default();
+    match event {
+        HandEvent::TableOpened {
+            seed,
+            players,
+            settings,
+        } => {
+            let mut engine = Engine::new(settings.clone(), *players, *seed)
+                .with_secret(UNREVEALED_SECRET)
+                .with_revealed_seeds(revealed_seeds);
+            let events = engine.start();
+            Some((engine, events))
+        }
+        HandEvent::TableRestored { state, absent } => {
+            let mut engine = Engine::restore(state.clone())
+                .with_secret(UNREVEALED_SECRET)
+                .with_revealed_seeds(revealed_seeds);
+            let events = engine.resume(absent);
+            Some((engine, events))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the server seeds revealed in the game of a table, which ends once the next table is logged.
+fn find_revealed_seeds(events: &[HandEvent]) -> HashMap<usize, Seed> {
+    events
+        .iter()
+        .take_while(|event| {
+            !matches!(
+                event,
+                HandEvent::TableOpened { .. } | HandEvent::TableRestored { .. }
+            )
+        })
+        .filter_map(|event| match event {
+            HandEvent::ShuffleRevealed {
+                hand_number,
+                server_seed,
+                ..
+            } => Some((*hand_number, shuffle::from_hex(server_seed).ok()?)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the logged events among the events of the engine.
+fn logged(events: Vec<Event>) -> VecDeque<HandEvent> {
+    events
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::Logged(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::betting::StraddleRule;
+    use crate::engine::{Action, TableSettings};
+    use crate::hand_log::HandLog;
+    use crate::raw_message::PlayerChoice;
+    use crate::rotation::Rotation;
+    use crate::variant::GameVariant;
+
+    /// Utility function for creating the settings of a table with blinds of 5 and 10.
+    fn settings(variant: GameVariant) -> TableSettings {
+        TableSettings {
+            variant,
+            betting: variant.default_betting_structure(),
+            rotation: Rotation::Fixed,
+            straddles: StraddleRule::Off,
+            small_blind: 5,
+            start_money: 100,
+            max_run_outs: 2,
+            rabbit_hunt_interval: None,
+            bomb_pot: None,
+            is_shuffle_audited: false,
+        }
+    }
+
+    /// Utility function for recording a new game, see `record_table`.
+    fn record(variant: GameVariant, players: usize, hands: usize) -> Vec<HandEvent> {
+        let opened = HandEvent::TableOpened {
+            seed: 42,
+            players,
+            settings: settings(variant),
+        };
+        record_table(opened, hands)
+    }
+
+    /// Utility function for recording the game of an opened or restored table, see `record_engine`.
+    fn record_table(table: HandEvent, hands: usize) -> Vec<HandEvent> {
+        let (engine, events) = open_table(&table, None).unwrap();
+        record_engine(table, engine, events, hands)
+    }
+
+    /// Utility function for recording the game of a table as the server does, from the first events of its engine,
+    /// in which the first player to act raises and everyone else calls,
+    /// until the hand limit is reached or a player has won. Every third hand is raised all in.
+    fn record_engine(
+        table: HandEvent,
+        mut engine: Engine,
+        mut events: Vec<Event>,
+        hands: usize,
+    ) -> Vec<HandEvent> {
+        let mut log = vec![table];
+        let mut is_raised = false;
+        let mut is_all_in = false;
+        loop {
+            let mut action = Action::Timeout;
+            for event in events {
+                match event {
+                    Event::Logged(event) => {
+                        if let HandEvent::HandStarted { hand_number, .. } = event {
+                            is_raised = false;
+                            is_all_in = hand_number % 3 == 0;
+                        }
+                        log.push(event);
+                    }
+                    Event::AwaitingBet { player, range } => {
+                        let choice = match is_raised {
+                            true => PlayerChoice::Pass,
+                            false if is_all_in => PlayerChoice::RaiseTo(range.maximal),
+                            false => PlayerChoice::RaiseTo(range.minimal),
+                        };
+                        is_raised = true;
+                        action = Action::Choice { player, choice };
+                    }
+                    Event::AwaitingDiscard { players } => {
+                        let player = players[0];
+                        let card = engine.seats()[player].cards[0];
+                        action = Action::Choice {
+                            player,
+                            choice: PlayerChoice::Discard(card),
+                        };
+                    }
+                    Event::GameEnd(_) => return log,
+                    _ => {}
+                }
+            }
+            let ended = log
+                .iter()
+                .filter(|event| matches!(event, HandEvent::HandEnded { .. }))
+                .count();
+            if ended == hands {
+                return log;
+            }
+            log.push(HandEvent::ActionApplied {
+                action: action.clone(),
+            });
+            events = engine.apply(action);
+        }
+    }
+
+    /// Testing that recorded games are replayed exactly, even after being written to a log.
+    #[test]
+    fn test_replay() {
+        let test_cases = vec![
+            (GameVariant::TexasHoldem, 2),
+            (GameVariant::Pineapple, 3),
+            (GameVariant::Stud, 3),
+            (GameVariant::DeuceToSevenTripleDraw, 2),
+        ];
+        for (variant, players) in test_cases {
+            let log = record(variant, players, 5);
+            let summary = replay(&log);
+            assert!(
+                matches!(summary, Ok(ReplaySummary { games: 1, hands, .. }) if hands > 0),
+                "Failed: {}. Expected a replay, got {:?}",
+                variant,
+                summary
+            );
+        }
+
+        let mut state = Engine::new(settings(GameVariant::TexasHoldem), 3, 42).state();
+        state.hand_number = 9;
+        state.dealer = 2;
+        state.small_blind = 10;
+        state.seats[0].coins = 40;
+        state.seats[1].coins = 160;
+        let absent = vec![];
+        let log = record_table(HandEvent::TableRestored { state, absent }, 3);
+        assert!(
+            matches!(
+                replay(&log),
+                Ok(ReplaySummary { games: 1, hands, .. }) if hands > 0
+            ),
+            "Failed: Expected the restored table to be replayed, got {:?}",
+            replay(&log)
+        );
+
+        let path = std::env::temp_dir().join(format!("replay-{}.log", std::process::id()));
+        let mut log = HandLog::open(&path).unwrap();
+        let games = [
+            record(GameVariant::TexasHoldem, 3, 4),
+            record(GameVariant::Stud, 2, 4),
+        ];
+        for event in games.iter().flatten() {
+            log.append(event.clone()).unwrap();
+        }
+        let events = HandLog::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(2, replay(&events).unwrap().games);
+    }
+
+    /// Testing that audited hands are replayed with the revealed seeds, as the secret of the table is not logged.
+    #[test]
+    fn test_replay_audited() {
+        let mut settings = settings(GameVariant::TexasHoldem);
+        settings.is_shuffle_audited = true;
+        let table = HandEvent::TableOpened {
+            seed: 42,
+            players: 3,
+            settings: settings.clone(),
+        };
+        let mut engine = Engine::new(settings, 3, 42).with_secret([9; 32]);
+        let events = engine.start();
+        let log = record_engine(table, engine, events, 4);
+        assert!(
+            matches!(
+                replay(&log),
+                Ok(ReplaySummary { games: 1, hands, .. }) if hands > 0
+            ),
+            "Failed: Expected the audited game to be replayed, got {:?}",
+            replay(&log)
+        );
+
+        // A hand which was interrupted before its shuffle was revealed
+        let started = log
+            .iter()
+            .position(|e| matches!(e, HandEvent::HandStarted { hand_number: 2, .. }))
+            .unwrap();
+        let unrevealed: Vec<HandEvent> = log
+            .iter()
+            .filter(|e| !matches!(e, HandEvent::ShuffleRevealed { hand_number: 2, .. }))
+            .cloned()
+            .collect();
+        assert_eq!(
+            Err(ReplayError::Unrevealed {
+                position: started + 1,
+                hand_number: 2,
+            }),
+            replay(&unrevealed)
+        );
+    }
+
+    /// Testing that the first difference between a log and its replay is reported.
+    #[test]
+    fn test_divergence() {
+        let log = record(GameVariant::TexasHoldem, 3, 3);
+        let position = |f: fn(&HandEvent) -> bool| log.iter().position(f).unwrap();
+
+        // A different payout, as if the payout rules changed
+        let mut changed = log.clone();
+        let award = position(|e| matches!(e, HandEvent::PotAwarded { .. }));
+        let HandEvent::PotAwarded { player, amount } = log[award].clone() else {
+            unreachable!()
+        };
+        changed[award] = HandEvent::PotAwarded {
+            player,
+            amount: amount + 1,
+        };
+        assert_eq!(
+            Err(ReplayError::Diverged(Box::new(Divergence {
+                position: award + 1,
+                hand_number: Some(1),
+                recorded: Some(changed[award].clone()),
+                replayed: Some(log[award].clone()),
+            }))),
+            replay(&changed)
+        );
+
+        // A missing action, after which the engine logs nothing
+        let mut changed = log.clone();
+        let action = position(|e| matches!(e, HandEvent::ActionApplied { .. }));
+        changed.remove(action);
+        assert_eq!(
+            Err(ReplayError::Diverged(Box::new(Divergence {
+                position: action + 1,
+                hand_number: Some(1),
+                recorded: Some(log[action + 1].clone()),
+                replayed: None,
+            }))),
+            replay(&changed)
+        );
+
+        // A log which ends in the middle of what the engine logged
+        let end = position(|e| matches!(e, HandEvent::HandEnded { .. }));
+        let Err(ReplayError::Diverged(divergence)) = replay(&log[..end]) else {
+            panic!("Failed: Expected the shortened log to diverge");
+        };
+        assert_eq!((end + 1, None), (divergence.position, divergence.recorded));
+
+        // A game interrupted in the middle of a hand and restored afterwards
+        let mut state = Engine::new(settings(GameVariant::TexasHoldem), 3, 7).state();
+        state.small_blind = 5;
+        let absent = vec![1];
+        let restored = record_table(HandEvent::TableRestored { state, absent }, 2);
+        let interrupted = [&log[..end], &restored[..]].concat();
+        assert_eq!(2, replay(&interrupted).unwrap().games);
+        let reopened = [&log[..end], &log[..]].concat();
+        let Err(ReplayError::Diverged(divergence)) = replay(&reopened) else {
+            panic!("Failed: Expected the reopened log to diverge");
+        };
+        assert_eq!(end + 1, divergence.position);
+
+        // A game closed in the middle of a hand, after which nothing is replayed
+        let closed = HandEvent::TableClosed {
+            refunded_hand: Some(1),
+        };
+        let closed = [&log[..end], &[closed], &log[end..]].concat();
+        assert_eq!(1, replay(&closed).unwrap().games);
+
+        // Events before the table was opened are skipped
+        let mut changed = log.clone();
+        changed.insert(0, log[end].clone());
+        assert_eq!(replay(&log), replay(&changed));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::betting::BettingStructure;
+use crate::variant::GameVariant;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// The variants of HORSE in the order they are played.
+pub const HORSE_VARIANTS: [GameVariant; 5] = [
+    GameVariant::TexasHoldem,
+    GameVariant::OmahaHiLo,
+    GameVariant::Razz,
+    GameVariant::Stud,
+    GameVariant::StudHiLo,
+];
+
+/// The amount of hands every variant of HORSE is played, unless specified otherwise.
+pub const DEFAULT_HANDS_PER_VARIANT: usize = 8;
+
+/// How the variant of a table changes between hands.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rotation {
+    /// The variant never changes.
+    Fixed,
+
+    /// HORSE: the variants of `HORSE_VARIANTS` are played in turn, each for `hands_per_variant` hands,
+    /// all of them with fixed-limit betting.
+    Horse { hands_per_variant: usize },
+
+    /// The player on the button chooses the variant of every hand, which is played with its usual betting structure.
+    DealersChoice,
+}
+
+impl Rotation {
+    /// Returns the variant the table starts with: HORSE starts with its first variant, unless the variant is part of it.
+    pub fn first_variant(&self, variant: GameVariant) -> GameVariant {
+        match self {
+            Self::Horse { .. } if !HORSE_VARIANTS.contains(&variant) => HORSE_VARIANTS[0],
+            _ => variant,
+        }
+    }
+
+    /// Returns the next variant and the amount of hands until it is played,
+    /// after the given amount of hands were played with the current variant.
+    /// Returns `None` if the next variant is not known in advance.
+    pub fn scheduled_change(
+        &self,
+        variant: GameVariant,
+        hands_played: usize,
+    ) -> Option<(GameVariant, usize)> {
+        match self {
+            Self::Horse { hands_per_variant } => {
+                let next = match HORSE_VARIANTS.iter().position(|v| *v == variant) {
+                    Some(index) => HORSE_VARIANTS[(index + 1) % HORSE_VARIANTS.len()],
+                    None => HORSE_VARIANTS[0],
+                };
+                Some((next, hands_per_variant.saturating_sub(hands_played)))
+            }
+            Self::Fixed | Self::DealersChoice => None,
+        }
+    }
+
+    /// Returns the variants the player on the button may choose from.
+    pub fn choices(&self) -> Vec<GameVariant> {
+        match self {
+            Self::DealersChoice => GameVariant::ALL.to_vec(),
+            Self::Fixed | Self::Horse { .. } => vec![],
+        }
+    }
+
+    /// Returns the betting structure a variant is played with in this rotation.
+    pub fn betting_structure(&self, variant: GameVariant) -> BettingStructure {
+        match self {
+            Self::Horse { .. } => BettingStructure::FixedLimit,
+            Self::Fixed | Self::DealersChoice => variant.default_betting_structure(),
+        }
+    }
+}
+
+impl TryFrom<&str> for Rotation {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "none" => Ok(Self::Fixed),
+            "horse" => Ok(Self::Horse {
+                hands_per_variant: DEFAULT_HANDS_PER_VARIANT,
+            }),
+            "dealerschoice" => Ok(Self::DealersChoice),
+            v => Err(format!("No rotation: {}", v)),
+        }
+    }
+}
+
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Fixed => "none",
+            Self::Horse { .. } => "horse",
+            Self::DealersChoice => "dealerschoice",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Testing the schedule of HORSE.
+    #[test]
+    fn test_horse_schedule() {
+        let horse = Rotation::Horse {
+            hands_per_variant: 3,
+        };
+        let test_cases = vec![
+            (GameVariant::TexasHoldem, 0, (GameVariant::OmahaHiLo, 3)),
+            (GameVariant::Razz, 2, (GameVariant::Stud, 1)),
+            (GameVariant::StudHiLo, 3, (GameVariant::TexasHoldem, 0)),
+            // Variants which are not part of HORSE are followed by the first variant
+            (GameVariant::PotLimitOmaha, 1, (GameVariant::TexasHoldem, 2)),
+        ];
+        for (variant, hands_played, expected) in test_cases {
+            let change = horse.scheduled_change(variant, hands_played);
+            assert_eq!(
+                Some(expected),
+                change,
+                "Failed: {} {}. Expected {:?}, got {:?}",
+                variant,
+                hands_played,
+                expected,
+                change
+            );
+        }
+        assert_eq!(
+            None,
+            Rotation::DealersChoice.scheduled_change(GameVariant::Razz, 5)
+        );
+        assert_eq!(
+            GameVariant::TexasHoldem,
+            horse.first_variant(GameVariant::PotLimitOmaha)
+        );
+        assert_eq!(GameVariant::Razz, horse.first_variant(GameVariant::Razz));
+    }
+}
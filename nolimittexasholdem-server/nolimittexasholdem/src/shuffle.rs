@@ -0,0 +1,162 @@
+use crate::cards::Card;
+use rand::prelude::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+
+/// A seed of 256 bits, as used by the auditable shuffle.
+pub type Seed = [u8; 32];
+
+/// Maximal length of a client seed in bytes. Longer seeds are ignored.
+pub const MAX_CLIENT_SEED_LENGTH: usize = 64;
+
+/// Derives the server seed of a hand from the secret of the table.
+/// Revealing it reveals neither the secret nor the server seeds of other hands.
+pub fn server_seed(secret: &Seed, hand_number: usize) -> Seed {
+    let mut hasher = Sha256::new();
+    hasher.update(secret);
+    hasher.update((hand_number as u64).to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Shuffles the deck with a ChaCha20 generator seeded with the server seed.
+/// This is the order the server commits to before the hand.
+pub fn server_order(mut deck: Vec<Card>, server_seed: &Seed) -> Vec<Card> {
+    deck.shuffle(&mut ChaCha20Rng::from_seed(*server_seed));
+    deck
+}
+
+/// Returns the commitment to the order of the deck:
+/// the hex encoded SHA-256 hash of the values of the cards, followed by the server seed.
+pub fn commitment(deck: &[Card], server_seed: &Seed) -> String {
+    let mut hasher = Sha256::new();
+    for card in deck {
+        hasher.update([card.value]);
+    }
+    hasher.update(server_seed);
+    to_hex(&hasher.finalize())
+}
+
+/// Mixes the server seed with the seeds the players added, in the order of the players.
+/// Players who added no seed count as an empty seed.
+pub fn mix(server_seed: &Seed, client_seeds: &[Option<String>]) -> Seed {
+    let mut hasher = Sha256::new();
+    hasher.update(server_seed);
+    for seed in client_seeds {
+        let bytes = seed.as_deref().unwrap_or("").as_bytes();
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    hasher.finalize().into()
+}
+
+/// Shuffles the committed order once more, with a ChaCha20 generator seeded with the mixed seed.
+/// The cards are dealt from the end of the returned deck.
+pub fn final_order(mut deck: Vec<Card>, mixed_seed: &Seed) -> Vec<Card> {
+    deck.shuffle(&mut ChaCha20Rng::from_seed(*mixed_seed));
+    deck
+}
+
+/// Checks a revealed shuffle and returns the order the cards were dealt in, from the end of the deck.
+/// The deck is the unshuffled deck of the variant, as returned by `GameVariant::deck`.
+/// Fails if the revealed server seed does not match the commitment.
+///
+/// ```
+/// use nolimittexasholdem::shuffle::{self, Seed};
+/// use nolimittexasholdem::GameVariant;
+///
+/// let server_seed: Seed = [7; 32];
+/// let deck = GameVariant::TexasHoldem.deck();
+/// let commitment = shuffle::commitment(&shuffle::server_order(deck.clone(), &server_seed), &server_seed);
+/// let client_seeds = vec![Some("lucky".to_string()), None];
+///
+/// let dealt = shuffle::verify(deck.clone(), &commitment, &shuffle::to_hex(&server_seed), &client_seeds);
+/// assert_eq!(52, dealt.unwrap().len());
+/// assert!(shuffle::verify(deck, &commitment, &shuffle::to_hex(&[8; 32]), &client_seeds).is_err());
+/// ```
+pub fn verify(
+    deck: Vec<Card>,
+    commitment: &str,
+    server_seed: &str,
+    client_seeds: &[Option<String>],
+) -> Result<Vec<Card>, String> {
+    let server_seed = from_hex(server_seed)?;
+    let order = server_order(deck, &server_seed);
+    if self::commitment(&order, &server_seed) != commitment {
+        return Err("The server seed does not match the commitment".into());
+    }
+    Ok(final_order(order, &mix(&server_seed, client_seeds)))
+}
+
+/// Encodes bytes as lowercase hex.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes a seed from hex.
+pub fn from_hex(hex: &str) -> Result<Seed, String> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(format!("No seed: {}", hex));
+    }
+    let mut seed = [0; 32];
+    for (i, byte) in seed.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+            .map_err(|_| format!("No seed: {}", hex))?;
+    }
+    Ok(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::GameVariant;
+
+    /// Testing that a revealed shuffle can be checked against its commitment.
+    #[test]
+    fn test_commit_reveal() {
+        let secret = [1; 32];
+        let server_seed = server_seed(&secret, 3);
+        assert_ne!(server_seed, self::server_seed(&secret, 4));
+
+        let deck = GameVariant::TexasHoldem.deck();
+        let order = server_order(deck.clone(), &server_seed);
+        let commitment = commitment(&order, &server_seed);
+        let client_seeds = vec![Some("abc".to_string()), None, Some("".to_string())];
+        let dealt = final_order(order.clone(), &mix(&server_seed, &client_seeds));
+
+        let revealed = to_hex(&server_seed);
+        assert_eq!(
+            Ok(dealt.clone()),
+            verify(deck.clone(), &commitment, &revealed, &client_seeds)
+        );
+        let test_cases = vec![
+            // Every client seed changes the order
+            (
+                vec![Some("abd".to_string()), None, Some("".to_string())],
+                true,
+            ),
+            // The seeds are bound to their players
+            (
+                vec![None, Some("abc".to_string()), Some("".to_string())],
+                true,
+            ),
+            (client_seeds.clone(), false),
+        ];
+        for (seeds, is_different) in test_cases {
+            let order = verify(deck.clone(), &commitment, &revealed, &seeds).unwrap();
+            assert_eq!(
+                is_different,
+                order != dealt,
+                "Failed: {:?}. Expected a different order: {}",
+                seeds,
+                is_different
+            );
+        }
+
+        // A different server seed does not match the commitment
+        let other = to_hex(&self::server_seed(&secret, 4));
+        assert!(verify(deck.clone(), &commitment, &other, &client_seeds).is_err());
+        assert!(verify(deck, &commitment, "xyz", &client_seeds).is_err());
+        assert_eq!(Ok(server_seed), from_hex(&revealed));
+    }
+}
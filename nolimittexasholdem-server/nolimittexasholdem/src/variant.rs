@@ -0,0 +1,330 @@
+use crate::betting::BettingStructure;
+use crate::cards::{self, Card};
+use std::fmt;
+use std::fmt::Formatter;
+
+/// A variant of poker which can be played at a table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameVariant {
+    /// Texas Hold'em: two hole cards, the best five of all seven cards count.
+    TexasHoldem,
+
+    /// Omaha: four hole cards, exactly two of them and three board cards count.
+    PotLimitOmaha,
+
+    /// Omaha Hi-Lo: like Omaha, but the pot is split between the best high hand
+    /// and the best ace-to-five low hand of eight or better, if there is one.
+    OmahaHiLo,
+
+    /// Short Deck Hold'em: like Texas Hold'em, but with a deck of 36 cards without 2 to 5.
+    /// A flush beats a full house and A-6-7-8-9 is a straight.
+    /// If `trips_beat_straight` is set, three of a kind also beats a straight.
+    ShortDeck { trips_beat_straight: bool },
+
+    /// Pineapple: like Texas Hold'em, but three hole cards are dealt and one is discarded before the flop.
+    Pineapple,
+
+    /// Crazy Pineapple: like Pineapple, but the third hole card is discarded after the flop.
+    CrazyPineapple,
+
+    /// Seven-Card Stud: no board, every player is dealt seven own cards over five streets,
+    /// some of them face up. The best five of the seven cards count.
+    Stud,
+
+    /// Seven-Card Stud Hi-Lo: like Seven-Card Stud, but the pot is split between the best high hand
+    /// and the best ace-to-five low hand of eight or better, if there is one.
+    StudHiLo,
+
+    /// Razz: like Seven-Card Stud, but the best ace-to-five low hand wins.
+    Razz,
+
+    /// 2-7 Triple Draw: no board, every player is dealt five cards and may exchange any of them
+    /// after each of the first three betting rounds. The best deuce-to-seven low hand wins.
+    DeuceToSevenTripleDraw,
+}
+
+/// The value of the lowest card of a short deck, which is a six.
+const SHORT_DECK_LOWEST_VALUE: u8 = 4;
+
+impl GameVariant {
+    /// All variants with their default options.
+    pub const ALL: [GameVariant; 10] = [
+        Self::TexasHoldem,
+        Self::PotLimitOmaha,
+        Self::OmahaHiLo,
+        Self::ShortDeck {
+            trips_beat_straight: false,
+        },
+        Self::Pineapple,
+        Self::CrazyPineapple,
+        Self::Stud,
+        Self::StudHiLo,
+        Self::Razz,
+        Self::DeuceToSevenTripleDraw,
+    ];
+
+    /// Returns the amount of hole cards every player is dealt at the start of a hand.
+    /// In stud games, the last of them is dealt face up.
+    pub fn hole_cards(&self) -> usize {
+        match self {
+            Self::TexasHoldem | Self::ShortDeck { .. } => 2,
+            Self::Pineapple | Self::CrazyPineapple | Self::Stud | Self::StudHiLo | Self::Razz => 3,
+            Self::PotLimitOmaha | Self::OmahaHiLo => 4,
+            Self::DeuceToSevenTripleDraw => 5,
+        }
+    }
+
+    /// Returns the amount of hole cards every player holds at the showdown, after all discards.
+    /// In stud games, these are all seven cards of the player.
+    pub fn showdown_hole_cards(&self) -> usize {
+        match self {
+            Self::Pineapple | Self::CrazyPineapple => 2,
+            Self::Stud | Self::StudHiLo | Self::Razz => 7,
+            _ => self.hole_cards(),
+        }
+    }
+
+    /// Returns the amount of cards on a complete board, which is zero in stud and draw games.
+    pub fn board_cards(&self) -> usize {
+        if self.is_stud() || self.draws() > 0 {
+            0
+        } else {
+            5
+        }
+    }
+
+    /// Returns the amount of streets with a betting round.
+    pub fn streets(&self) -> usize {
+        if self.is_stud() {
+            5
+        } else {
+            4
+        }
+    }
+
+    /// Returns whether every player is dealt own cards instead of a board, some of them face up.
+    pub fn is_stud(&self) -> bool {
+        matches!(self, Self::Stud | Self::StudHiLo | Self::Razz)
+    }
+
+    /// Returns the amount of draws, which follow the betting of the first streets.
+    pub fn draws(&self) -> usize {
+        match self {
+            Self::DeuceToSevenTripleDraw => 3,
+            _ => 0,
+        }
+    }
+
+    /// Returns the street after whose betting every player discards a hole card, starting with 0 before the flop.
+    pub fn discard_street(&self) -> Option<usize> {
+        match self {
+            Self::Pineapple => Some(0),
+            Self::CrazyPineapple => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Returns all cards of the deck the variant is played with, in order.
+    pub fn deck(&self) -> Vec<Card> {
+        let lowest_value = match self {
+            Self::TexasHoldem
+            | Self::PotLimitOmaha
+            | Self::OmahaHiLo
+            | Self::Pineapple
+            | Self::CrazyPineapple
+            | Self::Stud
+            | Self::StudHiLo
+            | Self::Razz
+            | Self::DeuceToSevenTripleDraw => 0,
+            Self::ShortDeck { .. } => SHORT_DECK_LOWEST_VALUE,
+        };
+        (0..52)
+            .map(|value| Card { value })
+            .filter(|card| card.value() >= lowest_value)
+            .collect()
+    }
+
+    /// Returns the betting structure the variant is usually played with.
+    pub fn default_betting_structure(&self) -> BettingStructure {
+        match self {
+            Self::TexasHoldem | Self::ShortDeck { .. } | Self::Pineapple | Self::CrazyPineapple => {
+                BettingStructure::NoLimit
+            }
+            Self::PotLimitOmaha | Self::OmahaHiLo => BettingStructure::PotLimit,
+            Self::Stud | Self::StudHiLo | Self::Razz | Self::DeuceToSevenTripleDraw => {
+                BettingStructure::FixedLimit
+            }
+        }
+    }
+
+    /// Returns whether the pot is split between the best high and the best low hand.
+    pub fn is_hi_lo(&self) -> bool {
+        match self {
+            Self::TexasHoldem
+            | Self::PotLimitOmaha
+            | Self::ShortDeck { .. }
+            | Self::Pineapple
+            | Self::CrazyPineapple
+            | Self::Stud
+            | Self::Razz
+            | Self::DeuceToSevenTripleDraw => false,
+            Self::OmahaHiLo | Self::StudHiLo => true,
+        }
+    }
+
+    /// Returns the value of the hole cards on a complete board.
+    /// In Razz and 2-7 Triple Draw, the value of the low hand is returned, as the best low hand wins.
+    pub fn value_of_hand(&self, hole_cards: &[Card], board: &[Card]) -> usize {
+        assert_eq!(hole_cards.len(), self.showdown_hole_cards());
+        assert_eq!(board.len(), self.board_cards());
+
+        let (hole_cards, board) = (to_tuples(hole_cards), to_tuples(board));
+        match self {
+            Self::TexasHoldem | Self::Pineapple | Self::CrazyPineapple => {
+                let cards: Vec<(u8, u8)> = hole_cards.into_iter().chain(board).collect();
+                cards::value_of_hand(&cards)
+            }
+            Self::PotLimitOmaha | Self::OmahaHiLo => {
+                cards::value_of_omaha_hand(&hole_cards, &board)
+            }
+            Self::ShortDeck {
+                trips_beat_straight,
+            } => {
+                let cards: Vec<(u8, u8)> = hole_cards.into_iter().chain(board).collect();
+                cards::value_of_short_deck_hand(&cards, *trips_beat_straight)
+            }
+            Self::Stud | Self::StudHiLo => cards::value_of_hand(&hole_cards),
+            Self::Razz => cards::low_value_of_best_five(&hole_cards),
+            Self::DeuceToSevenTripleDraw => cards::deuce_to_seven_value_of_five(&hole_cards),
+        }
+    }
+
+    /// Returns the low value of the hole cards on a complete board.
+    /// Returns `None` if the variant has no low hands or the hand does not qualify for the low half of the pot.
+    pub fn low_value_of_hand(&self, hole_cards: &[Card], board: &[Card]) -> Option<usize> {
+        assert_eq!(hole_cards.len(), self.showdown_hole_cards());
+        assert_eq!(board.len(), self.board_cards());
+
+        match self {
+            Self::OmahaHiLo => {
+                cards::value_of_omaha_low_hand(&to_tuples(hole_cards), &to_tuples(board))
+            }
+            Self::StudHiLo => cards::value_of_eight_or_better_low_hand(&to_tuples(hole_cards)),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the door card which has to bring in, in stud games.
+    /// In Seven-Card Stud and Stud Hi-Lo, this is the lowest card, in Razz the highest card with the ace counting low.
+    /// Ties are broken by the color, in the order clubs, diamonds, hearts, spades.
+    pub fn bring_in(&self, door_cards: &[Card]) -> Option<usize> {
+        let positions = door_cards.iter().enumerate();
+        match self {
+            Self::Razz => positions
+                .max_by_key(|(_, card)| ((card.value() + 1) % 13, card.color()))
+                .map(|(i, _)| i),
+            _ => positions
+                .min_by_key(|(_, card)| (card.value(), card.color()))
+                .map(|(i, _)| i),
+        }
+    }
+
+    /// Returns the value of the face up cards of a stud hand, which decides who acts first on later streets.
+    /// The highest value acts first, which is the best high hand in Seven-Card Stud and the best low hand in Razz.
+    pub fn value_of_up_cards(&self, up_cards: &[Card]) -> usize {
+        let up_cards = to_tuples(up_cards);
+        match self {
+            Self::Razz => cards::low_value_of_cards(&up_cards),
+            _ => cards::value_of_partial_hand(&up_cards),
+        }
+    }
+}
+
+/// Converts cards into the (color, value) representation of the hand evaluation.
+fn to_tuples(cards: &[Card]) -> Vec<(u8, u8)> {
+    cards.iter().map(|c| (c.color(), c.value())).collect()
+}
+
+impl TryFrom<&str> for GameVariant {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "holdem" => Ok(Self::TexasHoldem),
+            "plo" => Ok(Self::PotLimitOmaha),
+            "plo8" => Ok(Self::OmahaHiLo),
+            "shortdeck" => Ok(Self::ShortDeck {
+                trips_beat_straight: false,
+            }),
+            "pineapple" => Ok(Self::Pineapple),
+            "crazypineapple" => Ok(Self::CrazyPineapple),
+            "stud" => Ok(Self::Stud),
+            "stud8" => Ok(Self::StudHiLo),
+            "razz" => Ok(Self::Razz),
+            "27td" => Ok(Self::DeuceToSevenTripleDraw),
+            v => Err(format!("No variant: {}", v)),
+        }
+    }
+}
+
+impl fmt::Display for GameVariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::TexasHoldem => "holdem",
+            Self::PotLimitOmaha => "plo",
+            Self::OmahaHiLo => "plo8",
+            Self::ShortDeck { .. } => "shortdeck",
+            Self::Pineapple => "pineapple",
+            Self::CrazyPineapple => "crazypineapple",
+            Self::Stud => "stud",
+            Self::StudHiLo => "stud8",
+            Self::Razz => "razz",
+            Self::DeuceToSevenTripleDraw => "27td",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Testing the decks of the variants.
+    #[test]
+    fn test_decks() {
+        assert_eq!(GameVariant::TexasHoldem.deck().len(), 52);
+        let short_deck = GameVariant::ShortDeck {
+            trips_beat_straight: false,
+        }
+        .deck();
+        assert_eq!(short_deck.len(), 36);
+        assert!(short_deck.iter().all(|card| card.value() >= 4));
+        assert!(short_deck.contains(&Card::try_from("DC").unwrap()));
+    }
+
+    /// Testing which door card has to bring in.
+    #[test]
+    fn test_bring_in() {
+        let test_cases = vec![
+            (GameVariant::Stud, "A5 B0 C9", Some(1)),
+            // Ties are broken by the color
+            (GameVariant::Stud, "B0 A0 C9", Some(1)),
+            // The ace counts low in Razz, so the king is the highest card
+            (GameVariant::Razz, "AC BB C9", Some(1)),
+            (GameVariant::Razz, "AB BB C9", Some(1)),
+            (GameVariant::Stud, "", None),
+        ];
+        for (variant, door_cards, expected) in test_cases {
+            let cards: Vec<Card> = door_cards
+                .split_whitespace()
+                .map(|card| Card::try_from(card).unwrap())
+                .collect();
+            let bring_in = variant.bring_in(&cards);
+            assert_eq!(
+                expected, bring_in,
+                "Failed: {} {}. Expected {:?}, got {:?}",
+                variant, door_cards, expected, bring_in
+            );
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::time::{Duration, Instant};
+
+/// Time for the current hand to end once the table is closing, before it is refunded.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The clock of a table: when the engine times out, while the table is paused, and when a closing table closes.
+/// Every decision takes the current time, so the clock never looks at the time itself.
+pub(crate) struct TableClock {
+    /// The time the clock of the engine runs out, see `Event::Wait`.
+    deadline: Instant,
+
+    /// The time the table was paused, while the clock is frozen.
+    paused_since: Option<Instant>,
+
+    /// The time the current hand is refunded, once the table is closing.
+    close_by: Option<Instant>,
+}
+
+impl TableClock {
+    /// Creates a clock which already ran out.
+    pub(crate) fn new(now: Instant) -> Self {
+        Self {
+            deadline: now,
+            paused_since: None,
+            close_by: None,
+        }
+    }
+
+    /// Lets the engine wait for the duration. A clock started while the table is paused runs once it is resumed.
+    pub(crate) fn wait(&mut self, now: Instant, duration: Duration) {
+        self.deadline = self.paused_since.unwrap_or(now) + duration;
+    }
+
+    /// Pauses the table, which freezes the clock. A paused or closing table can not be paused.
+    /// Returns whether the table was paused.
+    pub(crate) fn pause(&mut self, now: Instant) -> bool {
+        if self.paused_since.is_some() || self.close_by.is_some() {
+            return false;
+        }
+        self.paused_since = Some(now);
+        true
+    }
+
+    /// Lets the clock continue with the time it had left if the table was paused. Returns whether it was.
+    pub(crate) fn resume(&mut self, now: Instant) -> bool {
+        match self.paused_since.take() {
+            Some(paused_since) => {
+                self.deadline += now.saturating_duration_since(paused_since);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Closes the table once the current hand ended, or refunds the hand if closing was requested before.
+    /// A paused table is resumed, so the hand can end. Returns whether closing was requested before.
+    pub(crate) fn request_close(&mut self, now: Instant) -> bool {
+        if self.close_by.is_none() {
+            self.resume(now);
+            self.close_by = Some(now + CLOSE_TIMEOUT);
+            false
+        } else {
+            self.close_by = Some(now);
+            true
+        }
+    }
+
+    /// Returns whether the table is closing, so it is closed before the next hand is dealt.
+    pub(crate) fn is_closing(&self) -> bool {
+        self.close_by.is_some()
+    }
+
+    /// Returns whether the table is closing and the current hand took too long, so it is refunded.
+    pub(crate) fn is_refund_due(&self, now: Instant) -> bool {
+        self.close_by.is_some_and(|close_by| close_by <= now)
+    }
+
+    /// Returns whether the clock of the engine ran out. The clock of a paused table never does.
+    pub(crate) fn is_timed_out(&self, now: Instant) -> bool {
+        self.paused_since.is_none() && self.deadline <= now
+    }
+
+    /// Returns the time the game has to wake up at, or `None` if it waits for the next message however long it takes.
+    pub(crate) fn wake_up(&self) -> Option<Instant> {
+        match (self.paused_since, self.close_by) {
+            (Some(_), _) => None,
+            (None, Some(close_by)) => Some(self.deadline.min(close_by)),
+            (None, None) => Some(self.deadline),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Testing that a paused table freezes the clock, even for clocks started while it is paused.
+    #[test]
+    fn test_pause() {
+        let start = Instant::now();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+        // The time of the wait, of the pause, of the resume and the expected timeout
+        let test_cases = vec![
+            (0, 4, 100, 106),
+            (0, 0, 5, 15),
+            (5, 0, 20, 30),
+            (20, 15, 20, 30),
+        ];
+        for (wait, pause, resume, expected) in test_cases {
+            let mut clock = TableClock::new(start);
+            if wait <= pause {
+                clock.wait(at(wait), Duration::from_secs(10));
+                assert!(clock.pause(at(pause)));
+            } else {
+                assert!(clock.pause(at(pause)));
+                clock.wait(at(wait), Duration::from_secs(10));
+            }
+            assert!(!clock.pause(at(pause)), "Failed: Paused twice");
+            assert_eq!(None, clock.wake_up());
+            assert!(
+                !clock.is_timed_out(at(1000)),
+                "Failed: Paused clock timed out"
+            );
+
+            assert!(clock.resume(at(resume)));
+            assert!(!clock.resume(at(resume)), "Failed: Resumed twice");
+            assert_eq!(
+                Some(at(expected)),
+                clock.wake_up(),
+                "Failed: Wait at {}, pause at {}, resume at {}",
+                wait,
+                pause,
+                resume
+            );
+            assert!(!clock.is_timed_out(at(expected - 1)));
+            assert!(clock.is_timed_out(at(expected)));
+        }
+    }
+
+    /// Testing that a closing table refunds the hand once it took too long or closing was requested twice.
+    #[test]
+    fn test_close() {
+        let start = Instant::now();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+        // The times closing is requested at and the expected time of the refund
+        let test_cases = vec![
+            (vec![0], 60),
+            (vec![10], 70),
+            (vec![0, 30], 30),
+            (vec![0, 0], 0),
+        ];
+        for (requests, expected) in test_cases {
+            let mut clock = TableClock::new(start);
+            clock.wait(start, Duration::from_secs(1000));
+            assert!(!clock.is_closing());
+            for (i, request) in requests.iter().enumerate() {
+                assert_eq!(i > 0, clock.request_close(at(*request)));
+            }
+            assert!(clock.is_closing());
+            assert_eq!(Some(at(expected)), clock.wake_up());
+            assert!(
+                expected == 0 || !clock.is_refund_due(at(expected - 1)),
+                "Failed: Requests at {:?} refund early",
+                requests
+            );
+            assert!(
+                clock.is_refund_due(at(expected)),
+                "Failed: Requests at {:?} refund late",
+                requests
+            );
+        }
+    }
+
+    /// Testing that closing resumes a paused table, which can not be paused again.
+    #[test]
+    fn test_close_paused() {
+        let start = Instant::now();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+        let mut clock = TableClock::new(start);
+        clock.wait(start, Duration::from_secs(10));
+        assert!(clock.pause(at(5)));
+        assert!(!clock.request_close(at(20)));
+        assert!(!clock.resume(at(30)), "Failed: Closing table still paused");
+        assert!(!clock.pause(at(30)), "Failed: Closing table paused");
+        // The clock had 5 seconds left when it was paused
+        assert_eq!(Some(at(25)), clock.wake_up());
+        assert!(clock.is_timed_out(at(25)));
+        assert!(!clock.is_refund_due(at(79)));
+        assert!(clock.is_refund_due(at(80)));
+    }
+}
@@ -0,0 +1,115 @@
+use nolimittexasholdem::hand_history::{HandHistory, HistoryFormat};
+use nolimittexasholdem::hand_log::HandEvent;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Collects the events of every hand and appends its hand history once the hand ended.
+/// In PokerStars format, there is one file per player, which only shows the hole cards of its player,
+/// as the tracking software of that player expects. In JSON, the whole table shares one file.
+pub(crate) struct HistoryWriter {
+    directory: PathBuf,
+    format: HistoryFormat,
+    table: String,
+    player_names: Vec<String>,
+
+    /// The id of hand number 0, so hands of different games get different ids.
+    first_hand_id: u64,
+
+    /// The start of the current hand in seconds since the Unix epoch.
+    started_at: u64,
+
+    events: Vec<HandEvent>,
+}
+
+impl HistoryWriter {
+    /// Creates the writer of the table at the port, whose files are put in the directory.
+    pub(crate) fn new(
+        directory: PathBuf,
+        port: u16,
+        player_names: Vec<String>,
+        format: HistoryFormat,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            format,
+            table: format!("Table {}", port),
+            player_names,
+            // Up to 1000 hands per game before the ids of games started a second apart overlap
+            first_hand_id: now() * 1000,
+            started_at: now(),
+            events: vec![],
+        })
+    }
+
+    /// Records the event and writes the hand history once the hand ended.
+    pub(crate) fn record(&mut self, event: &HandEvent) -> io::Result<()> {
+        if let HandEvent::HandStarted { .. } = event {
+            self.events.clear();
+            self.started_at = now();
+        }
+        self.events.push(event.clone());
+        let hand_number = match event {
+            HandEvent::HandEnded { hand_number, .. } => *hand_number,
+            _ => return Ok(()),
+        };
+        let history = HandHistory {
+            table: self.table.clone(),
+            hand_id: self.first_hand_id + hand_number as u64,
+            started_at: self.started_at,
+            player_names: self.player_names.clone(),
+            events: std::mem::take(&mut self.events),
+        };
+        let table = self.table.replace(' ', "-");
+        match self.format {
+            HistoryFormat::PokerStars => {
+                for player in 0..self.player_names.len() {
+                    let file_name = format!(
+                        "{}-seat{}-{}.txt",
+                        table,
+                        player + 1,
+                        sanitize(&history.player_name(player))
+                    );
+                    // Hands are separated by two empty lines
+                    self.append(&file_name, history.to_pokerstars(Some(player)) + "\n\n")?;
+                }
+            }
+            HistoryFormat::Json => {
+                self.append(&format!("{}.jsonl", table), history.to_json() + "\n")?
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends the text to the file of that name in the directory.
+    fn append(&self, file_name: &str, text: String) -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.directory.join(file_name))?;
+        file.write_all(text.as_bytes())
+    }
+}
+
+/// Returns the seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Replaces everything in a name which might not be allowed in a file name.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -1,11 +1,15 @@
 extern crate core;
 
-use clap::{command, Arg, ArgAction};
+use clap::{command, Arg, ArgAction, Command};
+use nolimittexasholdem::{betting, engine, hand_history, hand_log, replay, rotation, variant};
+use rand::RngCore;
 
-mod cards;
+mod clock;
 mod game;
+mod history;
 mod player;
-mod raw_message;
+mod snapshot;
+mod stream;
 
 /// Main entry point of the No Limit Texas Hold'em Server.
 fn main() {
@@ -19,6 +23,52 @@ fn main() {
                 .default_value("8080")
                 .value_parser(clap::value_parser!(u16)),
         )
+        .arg(
+            Arg::new("variant")
+                .short('g')
+                .long("game")
+                .action(ArgAction::Set)
+                .help("Specify the variant of poker: holdem, plo, plo8, shortdeck, pineapple, crazypineapple, stud, stud8, razz or 27td")
+                .default_value("holdem")
+                .value_parser(|s: &str| variant::GameVariant::try_from(s)),
+        )
+        .arg(
+            Arg::new("trips_beat_straight")
+                .long("trips-beat-straight")
+                .action(ArgAction::SetTrue)
+                .help("Let three of a kind beat a straight in short deck"),
+        )
+        .arg(
+            Arg::new("betting")
+                .long("betting")
+                .action(ArgAction::Set)
+                .help("Specify the betting structure: nl, pl or fl (default depends on the variant, ignored when rotating)")
+                .value_parser(|s: &str| betting::BettingStructure::try_from(s)),
+        )
+        .arg(
+            Arg::new("straddles")
+                .long("straddles")
+                .action(ArgAction::Set)
+                .help("Specify which straddles are allowed: off, single or restraddle")
+                .default_value("off")
+                .value_parser(|s: &str| betting::StraddleRule::try_from(s)),
+        )
+        .arg(
+            Arg::new("rotation")
+                .long("rotation")
+                .action(ArgAction::Set)
+                .help("Specify how the variant changes between hands: none, horse or dealerschoice")
+                .default_value("none")
+                .value_parser(|s: &str| rotation::Rotation::try_from(s)),
+        )
+        .arg(
+            Arg::new("hands_per_variant")
+                .long("hands-per-variant")
+                .action(ArgAction::Set)
+                .help("Specify how many hands every variant of horse is played")
+                .default_value("8")
+                .value_parser(clap::value_parser!(usize)),
+        )
         .arg(
             Arg::new("small_blind")
                 .short('b')
@@ -37,16 +87,237 @@ fn main() {
                 .default_value("1000")
                 .value_parser(clap::value_parser!(usize)),
         )
+        .arg(
+            Arg::new("max_run_outs")
+                .short('r')
+                .long("max-run-outs")
+                .action(ArgAction::Set)
+                .help("Specify how many times players may agree to run the board out when all in")
+                .default_value("2")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .action(ArgAction::Set)
+                .help("Specify the seed of the table, from which every hand is shuffled reproducibly unless the shuffle is audited")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            Arg::new("audited_shuffle")
+                .long("audited-shuffle")
+                .action(ArgAction::SetTrue)
+                .help("Commit to every deck before the hand and reveal the seeds afterwards, so players can check the deal"),
+        )
+        .arg(
+            Arg::new("hand_log")
+                .long("hand-log")
+                .action(ArgAction::Set)
+                .help("Specify the file every hand of the table is appended to (default: hands-<port>.log)"),
+        )
+        .arg(
+            Arg::new("hand_histories")
+                .long("hand-histories")
+                .action(ArgAction::Set)
+                .help("Write the hand histories of the table to files in that directory")
+                .value_parser(clap::value_parser!(std::path::PathBuf)),
+        )
+        .arg(
+            Arg::new("hand_history_format")
+                .long("hand-history-format")
+                .action(ArgAction::Set)
+                .help("Specify the format of the hand histories: pokerstars or json")
+                .default_value("pokerstars")
+                .value_parser(|s: &str| hand_history::HistoryFormat::try_from(s)),
+        )
+        .arg(
+            Arg::new("rabbit_hunt_interval")
+                .long("rabbit-hunt")
+                .action(ArgAction::Set)
+                .help("Allow rabbit hunting, but at most once every that many hands per player")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("bomb_pot_ante")
+                .long("bomb-pot-ante")
+                .action(ArgAction::Set)
+                .help("Allow bomb pots, in which every player antes that amount and the betting starts on the flop")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("bomb_pot_interval")
+                .long("bomb-pot-interval")
+                .action(ArgAction::Set)
+                .help("Make every hand whose number is a multiple of that interval a bomb pot")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("bomb_pot_vote")
+                .long("bomb-pot-vote")
+                .action(ArgAction::SetTrue)
+                .help("Let the players vote for a bomb pot before every hand"),
+        )
+        .arg(
+            Arg::new("bomb_pot_double_board")
+                .long("bomb-pot-double-board")
+                .action(ArgAction::SetTrue)
+                .help("Play bomb pots on two boards, each of which pays half of the pot"),
+        )
+        .arg(
+            Arg::new("snapshot")
+                .long("snapshot")
+                .action(ArgAction::Set)
+                .help("Save the state of the table to that file before every hand (default: snapshot-<port>.json)")
+                .value_parser(clap::value_parser!(std::path::PathBuf)),
+        )
+        .arg(
+            Arg::new("restore")
+                .long("restore")
+                .action(ArgAction::SetTrue)
+                .help("Continue the game saved in the snapshot, whose players reclaim their seats with their session tokens"),
+        )
+        .subcommand(
+            Command::new("replay")
+                .about("Play the games of a hand log once more and check that every outcome is the same")
+                .arg(
+                    Arg::new("log")
+                        .required(true)
+                        .help("The hand log to replay")
+                        .value_parser(clap::value_parser!(std::path::PathBuf)),
+                ),
+        )
         .get_matches();
 
+    if let Some(("replay", matches)) = matches.subcommand() {
+        replay_log(matches.get_one::<std::path::PathBuf>("log").unwrap());
+        return;
+    }
+
+    let mut variant = *matches.get_one::<variant::GameVariant>("variant").unwrap();
+    if let variant::GameVariant::ShortDeck {
+        trips_beat_straight,
+    } = &mut variant
+    {
+        *trips_beat_straight = matches.get_flag("trips_beat_straight");
+    }
+    let mut rotation = *matches.get_one::<rotation::Rotation>("rotation").unwrap();
+    if let rotation::Rotation::Horse { hands_per_variant } = &mut rotation {
+        *hands_per_variant = *matches.get_one::<usize>("hands_per_variant").unwrap();
+    }
+    let variant = rotation.first_variant(variant);
+    let bomb_pot = matches
+        .get_one::<usize>("bomb_pot_ante")
+        .map(|ante| engine::BombPotSettings {
+            ante: *ante,
+            interval: matches.get_one::<usize>("bomb_pot_interval").copied(),
+            is_voted: matches.get_flag("bomb_pot_vote"),
+            is_double_board: matches.get_flag("bomb_pot_double_board"),
+        });
+    let betting = match matches.get_one::<betting::BettingStructure>("betting") {
+        Some(betting) if rotation == rotation::Rotation::Fixed => *betting,
+        _ => rotation.betting_structure(variant),
+    };
+    let port = *matches.get_one::<u16>("port").unwrap();
+    let snapshot_path = matches
+        .get_one::<std::path::PathBuf>("snapshot")
+        .cloned()
+        .unwrap_or_else(|| format!("snapshot-{}.json", port).into());
+    let table = if matches.get_flag("restore") {
+        let path = &snapshot_path;
+        match snapshot::Snapshot::load(path) {
+            Ok(snapshot) => {
+                println!(
+                    "[SERVER] Restored hand {} of table seed {} from {}",
+                    snapshot.state.hand_number + 1,
+                    snapshot.state.seed,
+                    path.display()
+                );
+                game::Table::Restored(snapshot)
+            }
+            Err(e) => {
+                eprintln!("[SERVER] Could not restore {}: {}", path.display(), e);
+                std::process::exit(2);
+            }
+        }
+    } else {
+        let seed = matches
+            .get_one::<u64>("seed")
+            .copied()
+            .unwrap_or_else(rand::random);
+        println!("[SERVER] Table seed: {}", seed);
+        game::Table::New {
+            seed,
+            settings: engine::TableSettings {
+                variant,
+                betting,
+                rotation,
+                straddles: *matches
+                    .get_one::<betting::StraddleRule>("straddles")
+                    .unwrap(),
+                small_blind: *matches.get_one::<usize>("small_blind").unwrap(),
+                start_money: *matches.get_one::<usize>("start_money").unwrap(),
+                max_run_outs: *matches.get_one::<usize>("max_run_outs").unwrap(),
+                rabbit_hunt_interval: matches.get_one::<usize>("rabbit_hunt_interval").copied(),
+                bomb_pot,
+                is_shuffle_audited: matches.get_flag("audited_shuffle"),
+            },
+        }
+    };
+    // The secret of audited shuffles must not be guessed, so it never follows from the seed.
+    // Replays shuffle audited hands with the revealed server seeds instead.
+    let mut secret = [0; 32];
+    rand::rngs::OsRng.fill_bytes(&mut secret);
+    let log_path = matches
+        .get_one::<String>("hand_log")
+        .cloned()
+        .unwrap_or_else(|| format!("hands-{}.log", port));
+    let log = hand_log::HandLog::open(&log_path).unwrap();
+    println!("[SERVER] Hand log: {}", log_path);
+    // The first signal closes the table after the current hand, the second one refunds the hand
+    let (stop_sender, stop) = async_std::channel::unbounded();
+    ctrlc::set_handler(move || {
+        let _ = stop_sender.try_send(());
+    })
+    .unwrap();
     async_std::task::block_on(async {
         game::start(
-            async_std::future::pending(),
-            *matches.get_one::<u16>("port").unwrap(),
-            *matches.get_one::<usize>("small_blind").unwrap(),
-            *matches.get_one::<usize>("start_money").unwrap(),
+            stop,
+            port,
+            table,
+            secret,
+            log,
+            matches
+                .get_one::<std::path::PathBuf>("hand_histories")
+                .map(|directory| {
+                    let format =
+                        matches.get_one::<hand_history::HistoryFormat>("hand_history_format");
+                    (directory.clone(), *format.unwrap())
+                }),
+            snapshot_path,
         )
         .await
         .unwrap();
     });
 }
+
+/// Replays the hand log at the path and reports the first divergence.
+/// Exits with an error if the log can not be read or the engine logs anything else.
+fn replay_log(path: &std::path::Path) {
+    let events = match hand_log::HandLog::read(path) {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("[REPLAY] Could not read {}: {}", path.display(), e);
+            std::process::exit(2);
+        }
+    };
+    match replay::replay(&events) {
+        Ok(summary) => println!(
+            "[REPLAY] Replayed {} games with {} hands and {} actions: every outcome matches",
+            summary.games, summary.hands, summary.actions
+        ),
+        Err(error) => {
+            eprintln!("[REPLAY] {}", error);
+            std::process::exit(1);
+        }
+    }
+}
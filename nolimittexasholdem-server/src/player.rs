@@ -1,9 +1,9 @@
-use crate::cards::{self, Card};
-use crate::raw_message::RawMessage;
+use crate::stream;
 use async_std::channel::Sender;
 use async_std::net::TcpStream;
 use async_std::prelude::FutureExt;
 use async_std::sync::Arc;
+use nolimittexasholdem::raw_message::RawMessage;
 use std::error::Error;
 use std::net::Shutdown;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -20,19 +20,15 @@ pub struct PlayerCommunication {
 
 /// A player associated with the server.
 pub struct Player {
-    socket: TcpStream,
+    /// The connection of the player, unless the player did not return to a restored game.
+    socket: Option<TcpStream>,
     player_id: usize,
     pub begin_game: bool,
     pub display_name: String,
-    pub cards: Vec<Card>,
-    connection_status: Arc<AtomicBool>,
-    pub has_folded: bool,
-    coins: usize,
-    pub is_out: bool,
-    current_betting_amount: usize,
 
-    /// The value of the cards when the player wants to show his cards.
-    pub end_of_round_values: Option<usize>,
+    /// The secret with which the player reclaims the seat once a saved game is restored.
+    pub session_token: String,
+    connection_status: Arc<AtomicBool>,
 }
 
 impl Player {
@@ -41,30 +37,39 @@ impl Player {
         socket: TcpStream,
         display_name: String,
         player_id: usize,
+        session_token: String,
         sender: Sender<PlayerCommunication>,
     ) -> Result<Self, Box<dyn Error>> {
         let me = Self {
-            socket,
+            socket: Some(socket),
             player_id,
             display_name,
+            session_token,
             begin_game: false,
-            cards: vec![],
             connection_status: Arc::new(AtomicBool::new(true)),
-            has_folded: false,
-            coins: 0,
-            is_out: false,
-            current_betting_amount: 0,
-            end_of_round_values: None,
         };
 
         me.spawn_reader_task(player_id, sender)?;
         Ok(me)
     }
 
+    /// Creates a player of a restored game who has not reclaimed the seat (yet).
+    pub fn absent(display_name: String, player_id: usize, session_token: String) -> Self {
+        Self {
+            socket: None,
+            player_id,
+            display_name,
+            session_token,
+            begin_game: false,
+            connection_status: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
     /// Writes a message to the socket.
     pub(crate) async fn write_message(&mut self, msg: RawMessage) {
-        if self.is_connected() {
-            if let Err(e) = msg.to_stream(&mut self.socket).await {
+        let is_connected = self.is_connected();
+        if let Some(socket) = self.socket.as_mut().filter(|_| is_connected) {
+            if let Err(e) = stream::write_to_stream(msg, socket).await {
                 println!(
                     "[SERVER] Client {} was unable to be written to {:?}",
                     self.player_id, e
@@ -81,15 +86,12 @@ impl Player {
         sender: Sender<PlayerCommunication>,
     ) -> Result<(), Box<dyn Error>> {
         let connection_status = self.connection_status.clone();
-        let socket = self.socket.clone();
+        let socket = self.socket.clone().expect("Only connected players read");
 
         // Task handle is dropped
         // Task is executing on its own
         async_std::task::spawn(async move {
-            let res = Self::reader(socket, sender, connection_status, player_id).await;
-            match res {
-                _ => {}
-            }
+            Self::reader(socket, sender, connection_status, player_id).await;
         });
 
         Ok(())
@@ -131,7 +133,7 @@ impl Player {
         // connection alive, if the value is lowered
         const TIMEOUT: u64 = 3000;
         loop {
-            let computed_msg = RawMessage::read_from_stream(socket)
+            let computed_msg = stream::read_from_stream(socket)
                 .timeout(Duration::from_secs(TIMEOUT))
                 .await??;
             println!("[SERVER] Received {} {:?}", player_id, computed_msg);
@@ -149,17 +151,6 @@ impl Player {
         }
     }
 
-    /// Determines the value of the hand.
-    pub fn determine_card_value(&self, table_cards: &Vec<Card>) -> usize {
-        assert_eq!(table_cards.len(), 5);
-        let mut tmp = self.cards.clone();
-        for x in table_cards {
-            tmp.push(x.clone());
-        }
-        let mut cards: Vec<(u8, u8)> = tmp.into_iter().map(|c| (c.color(), c.value())).collect();
-        cards::value_of_hand(&mut cards)
-    }
-
     /// Internal socket shutdown routine.
     fn shutdown_internal(socket: &TcpStream, connection_status: &Arc<AtomicBool>) {
         println!("[SERVER] Shutting down socket");
@@ -170,7 +161,9 @@ impl Player {
 
     /// Shuts down the socket related to the `Player`.
     pub fn shutdown(&self) {
-        Self::shutdown_internal(&self.socket, &self.connection_status)
+        if let Some(socket) = &self.socket {
+            Self::shutdown_internal(socket, &self.connection_status)
+        }
     }
 
     /// Obtain this `Player`'s identifier.
@@ -182,43 +175,4 @@ impl Player {
     pub fn is_connected(&self) -> bool {
         self.connection_status.load(Ordering::Relaxed)
     }
-
-    /// Obtain this `Player`'s amount of coins.
-    pub fn coins(&self) -> usize {
-        self.coins
-    }
-
-    /// Obtain this `Player`'s current betting amount.
-    pub fn current_betting_amount(&self) -> usize {
-        self.current_betting_amount
-    }
-
-    /// Subtracts the betting amount (but not more than max_amount) and returns it.
-    /// If any money is left over it is added back to the `Player`'s coins.
-    /// Resets the betting amount to zero.
-    pub fn take_betting_amount(&mut self, max_amount: usize) -> usize {
-        let amount_deduced = std::cmp::min(self.current_betting_amount, max_amount);
-        self.current_betting_amount -= amount_deduced;
-        self.coins += self.current_betting_amount;
-        self.current_betting_amount = 0;
-        amount_deduced
-    }
-
-    /// Add a specified amount of coins to the `Player`'s balance.
-    pub fn add_coins(&mut self, amount: usize) {
-        self.coins += amount;
-    }
-
-    /// Set the `Player`'s amount of coins.
-    pub fn set_coins(&mut self, coins: usize) {
-        self.coins = coins;
-    }
-
-    /// Deduct money from the `Player`'s balance for betting.
-    pub fn deduct_from_money_to_bet(&mut self, amount: usize) {
-        self.coins += self.current_betting_amount;
-        assert!(self.coins >= amount);
-        self.coins -= amount;
-        self.current_betting_amount = amount;
-    }
 }
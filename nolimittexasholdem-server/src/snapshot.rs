@@ -0,0 +1,58 @@
+use nolimittexasholdem::engine::TableState;
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A game saved between two hands: the state of the table and who sits at it.
+/// The players reclaim their seats with their session tokens once the game is restored.
+pub(crate) struct Snapshot {
+    pub(crate) state: TableState,
+
+    /// The display name and session token of every seat.
+    pub(crate) players: Vec<(String, String)>,
+}
+
+impl Snapshot {
+    /// Writes the snapshot to the path. The file is replaced at once, so a crash never leaves half a snapshot.
+    pub(crate) fn save(&self, path: &Path) -> io::Result<()> {
+        let players: Vec<Value> = self
+            .players
+            .iter()
+            .map(|(name, token)| json!({"name": name, "session_token": token}))
+            .collect();
+        let value = json!({
+            "state": Value::from(self.state.clone()),
+            "players": players
+        });
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, value.to_string())?;
+        fs::rename(temporary, path)
+    }
+
+    /// Reads the snapshot at the path.
+    pub(crate) fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let value: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        let state = TableState::try_from(value["state"].clone())?;
+        let players = value["players"]
+            .as_array()
+            .ok_or("Missing field: players")?
+            .iter()
+            .map(
+                |player| match (player["name"].as_str(), player["session_token"].as_str()) {
+                    (Some(name), Some(token)) => Ok((name.to_string(), token.to_string())),
+                    _ => Err(format!("No player: {}", player)),
+                },
+            )
+            .collect::<Result<Vec<(String, String)>, String>>()?;
+        if players.len() != state.seats.len() {
+            return Err(format!(
+                "{} players for {} seats",
+                players.len(),
+                state.seats.len()
+            ));
+        }
+        Ok(Self { state, players })
+    }
+}
@@ -0,0 +1,53 @@
+use async_std::io::{ReadExt, WriteExt};
+use async_std::net::TcpStream;
+use nolimittexasholdem::raw_message::RawMessage;
+use std::error::Error;
+use std::str::FromStr;
+
+/// Reads the size of an incoming byte stream of a TcpStream.
+async fn read_size_of_incoming(socket: &mut TcpStream) -> Result<usize, Box<dyn Error>> {
+    let mut all_buff = Vec::<u8>::new();
+    let mut buff = [0u8; 1024];
+
+    loop {
+        let read_amount = socket.peek(&mut buff).await?;
+        let ter = buff[0..read_amount].iter().position(|&b| b == b':');
+        if let Some(i) = ter {
+            // Null terminator is read
+            // But not attached to string!
+            socket.read_exact(&mut buff[0..(i + 1)]).await?;
+            all_buff.extend_from_slice(&buff[0..i]);
+            let string: String = String::from_utf8_lossy(&all_buff).into();
+            return Ok(usize::from_str(string.as_str())?);
+        } else {
+            // Read the bytes again until terminator found
+            // Maybe skip instead?
+            socket.read_exact(&mut buff[0..read_amount]).await?;
+            all_buff.extend_from_slice(&buff[0..read_amount]);
+        }
+    }
+}
+
+/// Utility function for reading from a TcpStream until we reach a zero terminator.
+async fn read_till_terminator(socket: &mut TcpStream) -> Result<String, Box<dyn Error>> {
+    let size = read_size_of_incoming(socket).await?;
+    let mut buff: Vec<u8> = vec![0; size];
+    socket.read_exact(&mut buff[0..size]).await?;
+    Ok(String::from_utf8_lossy(&buff).into())
+}
+
+/// Writes the content of the RawMessage to a TcpStream.
+pub(crate) async fn write_to_stream(
+    message: RawMessage,
+    socket: &mut TcpStream,
+) -> Result<(), Box<dyn Error>> {
+    socket.write_all(message.encode().as_bytes()).await?;
+    socket.flush().await?;
+    Ok(())
+}
+
+/// Creates a RawMessage by reading from a TcpStream.
+pub(crate) async fn read_from_stream(socket: &mut TcpStream) -> Result<RawMessage, Box<dyn Error>> {
+    let raw_string = read_till_terminator(socket).await?;
+    Ok(RawMessage::decode(&raw_string)?)
+}